@@ -0,0 +1,103 @@
+//! Solves the 15-puzzle with the generic search module.
+
+use rubiks_cube::search::{self, Heuristic, Keyed, Prunable, SearchNode, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slide {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Slide {
+    fn inverse(&self) -> Slide {
+        match self {
+            Slide::Up => Slide::Down,
+            Slide::Down => Slide::Up,
+            Slide::Left => Slide::Right,
+            Slide::Right => Slide::Left,
+        }
+    }
+}
+
+/// Tiles in row-major order, 0 for the blank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Board([u8; 16]);
+
+impl Board {
+    fn blank(&self) -> usize {
+        self.0.iter().position(|x| *x == 0).unwrap()
+    }
+
+    /// Moves the blank in the direction, if possible.
+    fn slide(&self, slide: Slide) -> Option<Board> {
+        let blank = self.blank();
+        let (row, col) = (blank / 4, blank % 4);
+        let target = match slide {
+            Slide::Up if row > 0 => blank - 4,
+            Slide::Down if row < 3 => blank + 4,
+            Slide::Left if col > 0 => blank - 1,
+            Slide::Right if col < 3 => blank + 1,
+            _ => return None,
+        };
+
+        let mut tiles = self.0;
+        tiles.swap(blank, target);
+        Some(Board(tiles))
+    }
+}
+
+impl State for Board {
+    type Move = Slide;
+
+    fn is_goal(&self) -> bool {
+        self.estimate() == 0
+    }
+
+    fn moves(&self, last: Option<&Slide>) -> impl IntoIterator<Item = (Slide, Self)> {
+        [Slide::Up, Slide::Down, Slide::Left, Slide::Right]
+            .into_iter()
+            .filter(move |slide| last.map(Slide::inverse) != Some(*slide))
+            .filter_map(|slide| Some((slide, self.slide(slide)?)))
+    }
+}
+
+impl Keyed for Board {
+    type Key = [u8; 16];
+
+    fn key(&self) -> Self::Key {
+        self.0
+    }
+}
+
+impl Heuristic for Board {
+    /// Sum of Manhattan distances of the tiles from their positions.
+    fn estimate(&self) -> usize {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| **tile != 0)
+            .map(|(i, tile)| {
+                let goal = *tile as usize - 1;
+                (i / 4).abs_diff(goal / 4) + (i % 4).abs_diff(goal % 4)
+            })
+            .sum()
+    }
+}
+
+impl Prunable for Board {
+    fn to_prune(&self, depth: usize, max_depth: usize) -> bool {
+        self.estimate() > max_depth - depth
+    }
+}
+
+fn main() {
+    let board = Board([1, 2, 3, 4, 5, 6, 0, 8, 9, 10, 7, 11, 13, 14, 15, 12]);
+
+    let result = search::iterative_deepening_dfs(SearchNode::root(board.clone()), 20);
+    println!("IDA*: {:?}", result.map(|node| node.path()));
+
+    let result = search::a_star_search(SearchNode::root(board), 20);
+    println!("A*:   {:?}", result.map(|node| node.path()));
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+/// Options followed by a value, as `--name value` or `--name=value`.
+const VALUE_OPTIONS: [&str; 13] = [
+    "format", "method", "strategy", "depth", "length", "workers", "corpus", "repeat", "save",
+    "baseline", "session", "port", "timeout",
+];
+
+/// Command line arguments: the command, its positional arguments and
+/// options with values.
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    pub fn parse(args: Vec<String>) -> Result<Self> {
+        let mut result = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                result.positional.push(arg);
+                continue;
+            };
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+            if !VALUE_OPTIONS.contains(&name) {
+                return Err(anyhow!("unknown option: --{name}"));
+            }
+            let value = match value {
+                Some(value) => value,
+                None => args
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value of --{name}"))?,
+            };
+            result.options.insert(name.to_string(), value);
+        }
+
+        Ok(result)
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.positional.first().map(String::as_str)
+    }
+
+    /// Returns the positional arguments after the command.
+    pub fn operands(&self) -> &[String] {
+        self.positional.get(1..).unwrap_or_default()
+    }
+
+    /// Parses the value of the option, if given.
+    pub fn value<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.options
+            .get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| anyhow!("invalid value of --{name}: {e}"))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let args = ["--format", "json", "solve", "R U", "--depth=5"];
+        let args = Args::parse(args.map(String::from).to_vec())?;
+
+        assert_eq!(args.command(), Some("solve"));
+        assert_eq!(args.operands(), ["R U"]);
+        assert_eq!(args.value::<usize>("depth")?, Some(5));
+        assert_eq!(args.value::<String>("format")?.as_deref(), Some("json"));
+        assert!(args.value::<usize>("format").is_err());
+        assert!(Args::parse(vec!["--depth".to_string()]).is_err());
+        assert!(Args::parse(vec!["--verbose".to_string()]).is_err());
+        assert!(Args::parse(vec!["--dpeth=5".to_string()]).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+
+use super::args::Args;
+use super::solve::{SolveOptions, SolveReport};
+use super::{Format, emit};
+
+/// Result of solving a line of the input, numbered from 1. Lines which are
+/// not valid scrambles or facelet strings are reported with their errors.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchResult {
+    pub line: usize,
+    pub input: String,
+    #[serde(flatten)]
+    pub report: Option<SolveReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Display for BatchResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.report, &self.error) {
+            (Some(report), _) => write!(f, "Line {}:\n{report}", self.line),
+            (None, error) => writeln!(
+                f,
+                "Line {}: error: {}",
+                self.line,
+                error.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Returns the lines of the input without their line endings, as bytes so
+/// that lines which are not valid UTF-8 can be reported on their own.
+pub fn read_lines(mut input: impl BufRead) -> impl Iterator<Item = io::Result<Vec<u8>>> {
+    std::iter::from_fn(move || {
+        let mut line = vec![];
+        match input.read_until(b'\n', &mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with(b"\n") {
+                    line.pop();
+                    if line.ends_with(b"\r") {
+                        line.pop();
+                    }
+                }
+                Some(Ok(line))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    })
+}
+
+/// Solves the lines with `workers` threads and passes the results to
+/// `output` in the order of the lines as soon as they are solved. Blank
+/// lines are skipped and lines which are not valid UTF-8 are reported as
+/// errors.
+pub fn solve_lines(
+    lines: impl Iterator<Item = io::Result<Vec<u8>>> + Send,
+    options: &SolveOptions,
+    workers: usize,
+    mut output: impl FnMut(BatchResult) -> Result<()>,
+) -> Result<()> {
+    let workers = workers.max(1);
+    // bound the lines read ahead so that large inputs are streamed
+    let (job_sender, jobs) = mpsc::sync_channel::<(usize, usize, Vec<u8>)>(workers * 4);
+    let jobs = Arc::new(Mutex::new(jobs));
+    let (result_sender, results) = mpsc::channel();
+
+    thread::scope(|scope| {
+        let reader = scope.spawn(move || -> io::Result<()> {
+            let lines = lines
+                .enumerate()
+                .filter(|(_, line)| !matches!(line, Ok(line) if line.trim_ascii().is_empty()));
+            for (index, (i, line)) in lines.enumerate() {
+                if job_sender.send((index, i + 1, line?)).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        });
+
+        for _ in 0..workers {
+            let jobs = Arc::clone(&jobs);
+            let results = result_sender.clone();
+            scope.spawn(move || {
+                loop {
+                    let job = jobs.lock().unwrap().recv();
+                    let Ok((index, line, input)) = job else {
+                        break;
+                    };
+                    let (input, report, error) = match String::from_utf8(input) {
+                        Ok(input) => match options.solve(&input) {
+                            Ok(report) => (input, Some(report), None),
+                            Err(e) => (input, None, Some(e.to_string())),
+                        },
+                        Err(e) => {
+                            let error =
+                                format!("invalid UTF-8 at byte {}", e.utf8_error().valid_up_to());
+                            let input = String::from_utf8_lossy(e.as_bytes()).into_owned();
+                            (input, None, Some(error))
+                        }
+                    };
+                    let result = BatchResult {
+                        line,
+                        input,
+                        report,
+                        error,
+                    };
+                    if results.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // once the workers stop, the reader must fail to send the next lines
+        // instead of waiting for room in the channel
+        drop(jobs);
+        drop(result_sender);
+
+        // results arrive in any order and are held until the results of the
+        // lines before them are written
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (index, result) in results {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next) {
+                output(result)?;
+                next += 1;
+            }
+        }
+
+        reader
+            .join()
+            .map_err(|_| anyhow!("the reader of the input panicked"))??;
+        Ok(())
+    })
+}
+
+pub fn run(args: &Args, format: Format) -> Result<()> {
+    let options = SolveOptions::from_args(args)?;
+    let workers = match args.value("workers")? {
+        Some(workers) => workers,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let input: Box<dyn BufRead + Send> = match args.operands() {
+        [] => Box::new(BufReader::new(io::stdin())),
+        [path] if path == "-" => Box::new(BufReader::new(io::stdin())),
+        [path] => Box::new(BufReader::new(
+            File::open(path).map_err(|e| anyhow!("cannot open {path}: {e}"))?,
+        )),
+        _ => return Err(anyhow!("expected a single input file")),
+    };
+
+    solve_lines(read_lines(input), &options, workers, |result| {
+        emit(format, &result)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::solve::Method;
+    use rubiks_cube::Strategy;
+
+    #[test]
+    fn test_solve_lines() -> Result<()> {
+        let input = "R U\n\nF2 D'\nR X\nB\nL2 U' R\n";
+        let options = SolveOptions {
+            method: Method::Search(Strategy::IterativeDeepening),
+            max_depth: 3,
+        };
+        let mut results = vec![];
+        solve_lines(read_lines(input.as_bytes()), &options, 3, |r| {
+            results.push(r);
+            Ok(())
+        })?;
+
+        let lines = results.iter().map(|r| r.line).collect::<Vec<_>>();
+        assert_eq!(lines, [1, 3, 4, 5, 6]);
+        assert_eq!(results[2].error.as_deref(), Some("invalid operation: X"));
+        assert!(
+            results
+                .iter()
+                .all(|r| r.report.is_some() != r.error.is_some())
+        );
+
+        let json = serde_json::to_value(&results[4])?;
+        assert_eq!(json["line"], 6);
+        assert_eq!(json["solution"], serde_json::json!(["R'", "U", "L2"]));
+        assert!(json.get("error").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_lines_invalid_utf8() -> Result<()> {
+        let input: &[u8] = b"R U\r\nF \xff\xfe\nF2 D'\n";
+        let options = SolveOptions {
+            method: Method::Search(Strategy::IterativeDeepening),
+            max_depth: 3,
+        };
+        let mut results = vec![];
+        solve_lines(read_lines(input), &options, 2, |r| {
+            results.push(r);
+            Ok(())
+        })?;
+
+        let lines = results.iter().map(|r| r.line).collect::<Vec<_>>();
+        assert_eq!(lines, [1, 2, 3]);
+        assert_eq!(results[0].input, "R U");
+        assert_eq!(results[1].input, "F \u{fffd}\u{fffd}");
+        assert_eq!(results[1].error.as_deref(), Some("invalid UTF-8 at byte 2"));
+        assert!(results[0].report.is_some() && results[2].report.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_lines_output_error() {
+        let lines = std::iter::repeat_n("R U", 10_000).map(|l| Ok(l.as_bytes().to_vec()));
+        let options = SolveOptions {
+            method: Method::Search(Strategy::IterativeDeepening),
+            max_depth: 2,
+        };
+        let mut written = 0;
+        let result = solve_lines(lines, &options, 2, |_| {
+            written += 1;
+            match written {
+                3 => Err(anyhow!("broken pipe")),
+                _ => Ok(()),
+            }
+        });
+
+        assert_eq!(result.unwrap_err().to_string(), "broken pipe");
+        assert_eq!(written, 3);
+    }
+}
@@ -0,0 +1,334 @@
+use std::fmt::Display;
+use std::fs;
+use std::io::Write;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use super::args::Args;
+use super::solve::{Method, SolveOptions};
+use super::{Format, emit};
+
+/// Scrambles solved by default, checked in so that runs are comparable.
+const CORPUS: &str = include_str!("../../bench/corpus.txt");
+
+/// Scramble of the corpus with its group and its length, which bounds the
+/// length of its optimal solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    group: String,
+    length: usize,
+    scramble: String,
+}
+
+/// Parses the lines `<group> <length> <scramble>` of a corpus, skipping
+/// blank lines and comments starting with `#`.
+fn parse_corpus(text: &str) -> Result<Vec<Entry>> {
+    let mut entries = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.splitn(3, char::is_whitespace);
+        let (Some(group), Some(length), Some(scramble)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(anyhow!(
+                "line {}: expected a group, a length and moves",
+                i + 1
+            ));
+        };
+        let length = length
+            .parse()
+            .map_err(|_| anyhow!("line {}: invalid length: {length}", i + 1))?;
+        entries.push(Entry {
+            group: group.to_string(),
+            length,
+            scramble: scramble.trim().to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Mean, median and 95th percentile of values.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Summary {
+    pub mean: f64,
+    pub median: f64,
+    pub p95: f64,
+}
+
+impl Summary {
+    pub fn new(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        // nearest rank percentiles
+        let percentile = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+
+        Self {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            median: percentile(0.5),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+/// Changes of the medians from a baseline as ratios, e.g. 0.1 for 10%
+/// slower.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub seconds: f64,
+    pub nodes: f64,
+}
+
+/// Results of a group of the corpus. Groups with scrambles longer than the
+/// maximum depth of the search, which would be hopeless, are solved with
+/// Thistlethwaite's method instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupReport {
+    pub group: String,
+    /// Name of the method solving the group
+    #[serde(default)]
+    pub method: String,
+    pub runs: usize,
+    pub seconds: Summary,
+    pub nodes: Summary,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<Comparison>,
+}
+
+impl GroupReport {
+    fn compare(&mut self, baseline: &[GroupReport]) {
+        let change = |current: f64, base: f64| {
+            if base > 0.0 {
+                current / base - 1.0
+            } else {
+                0.0
+            }
+        };
+        self.baseline = baseline
+            .iter()
+            .find(|b| b.group == self.group && b.method == self.method && b.runs > 0)
+            .map(|b| Comparison {
+                seconds: change(self.seconds.median, b.seconds.median),
+                nodes: change(self.nodes.median, b.nodes.median),
+            });
+    }
+}
+
+impl Display for GroupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {} runs", self.group, self.method, self.runs)?;
+        if self.runs > 0 {
+            let Summary { mean, median, p95 } = self.seconds;
+            write!(
+                f,
+                ", time mean {:.2} ms / median {:.2} ms / p95 {:.2} ms",
+                mean * 1e3,
+                median * 1e3,
+                p95 * 1e3
+            )?;
+            let Summary { mean, median, p95 } = self.nodes;
+            write!(f, ", nodes mean {mean:.0} / median {median} / p95 {p95}")?;
+        }
+        if let Some(baseline) = &self.baseline {
+            write!(
+                f,
+                ", vs baseline: time {:+.1}%, nodes {:+.1}%",
+                baseline.seconds * 100.0,
+                baseline.nodes * 100.0
+            )?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Solves the entries `repeat` times each, reporting each group in the
+/// order of the corpus.
+fn bench(entries: &[Entry], options: &SolveOptions, repeat: usize) -> Result<Vec<GroupReport>> {
+    let mut groups: Vec<(String, Method)> = vec![];
+    for entry in entries {
+        let method = match options.method {
+            Method::Search(_) if entry.length > options.max_depth => Method::Thistlethwaite,
+            method => method,
+        };
+        match groups.iter_mut().find(|(group, _)| *group == entry.group) {
+            // a group is solved with a single method to be comparable
+            Some((_, m)) if method == Method::Thistlethwaite => *m = method,
+            Some(_) => {}
+            None => groups.push((entry.group.clone(), method)),
+        }
+    }
+
+    // load the tables of the methods before measuring
+    if let Some(entry) = entries.first() {
+        for (i, (_, method)) in groups.iter().enumerate() {
+            if groups[..i].iter().all(|(_, m)| m != method) {
+                let options = SolveOptions {
+                    method: *method,
+                    max_depth: 0,
+                };
+                options.solve(&entry.scramble)?;
+            }
+        }
+    }
+
+    let mut reports = vec![];
+    for (group, method) in groups {
+        let (mut seconds, mut nodes) = (vec![], vec![]);
+        for entry in entries.iter().filter(|e| e.group == group) {
+            // the search is bounded by the scramble
+            let options = SolveOptions {
+                method,
+                max_depth: entry.length,
+            };
+            for _ in 0..repeat {
+                let report = options.solve(&entry.scramble)?;
+                if report.solution.is_none() {
+                    return Err(anyhow!("no solution found for {}", entry.scramble));
+                }
+                seconds.push(report.seconds);
+                nodes.push(report.nodes as f64);
+            }
+        }
+        reports.push(GroupReport {
+            group,
+            method: method.name().to_string(),
+            runs: seconds.len(),
+            seconds: Summary::new(&seconds),
+            nodes: Summary::new(&nodes),
+            baseline: None,
+        });
+    }
+
+    Ok(reports)
+}
+
+/// Reads the reports of a previous run written as JSON Lines.
+fn read_baseline(path: &str) -> Result<Vec<GroupReport>> {
+    let text = fs::read_to_string(path).map_err(|e| anyhow!("cannot read {path}: {e}"))?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| anyhow!("invalid baseline {path}: {e}")))
+        .collect()
+}
+
+pub fn run(args: &Args, format: Format) -> Result<()> {
+    let entries = match args.value::<String>("corpus")? {
+        Some(path) => parse_corpus(
+            &fs::read_to_string(&path).map_err(|e| anyhow!("cannot read {path}: {e}"))?,
+        )?,
+        None => parse_corpus(CORPUS)?,
+    };
+    let options = SolveOptions::from_args(args)?;
+    let repeat = args.value("repeat")?.unwrap_or(1);
+    let baseline = match args.value::<String>("baseline")? {
+        Some(path) => read_baseline(&path)?,
+        None => vec![],
+    };
+
+    let mut reports = bench(&entries, &options, repeat)?;
+    for report in &mut reports {
+        report.compare(&baseline);
+        emit(format, report)?;
+    }
+
+    if let Some(path) = args.value::<String>("save")? {
+        let mut file = fs::File::create(&path).map_err(|e| anyhow!("cannot write {path}: {e}"))?;
+        for report in &reports {
+            let report = GroupReport {
+                baseline: None,
+                ..report.clone()
+            };
+            writeln!(file, "{}", serde_json::to_string(&report)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::solve::parse_cube;
+    use rubiks_cube::{Facelets, Strategy};
+
+    #[test]
+    fn test_corpus() -> Result<()> {
+        let entries = parse_corpus(CORPUS)?;
+        let superflip = entries.iter().find(|e| e.group == "superflip").unwrap();
+        let (scramble, cube) = parse_cube(&superflip.scramble)?;
+
+        assert!(entries.iter().any(|e| e.group == "depth-7"));
+        assert!(
+            entries
+                .iter()
+                .all(|e| parse_cube(&e.scramble).unwrap().0.unwrap().len() == e.length)
+        );
+        assert_eq!(scramble.map(|s| s.len()), Some(20));
+        // every edge flipped in place, and everything else solved
+        let solved = Facelets::default().to_string();
+        let facelets = Facelets::from_rotation(&cube.rotation).to_string();
+        let differences = solved.chars().zip(facelets.chars()).filter(|(a, b)| a != b);
+        assert_eq!(differences.count(), 24);
+        for i in 0..12 {
+            assert_eq!(cube.rotation.edge(i), (i, 1));
+        }
+        assert!(parse_corpus("depth-4 R U").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new(&[5.0, 1.0, 3.0, 2.0, 4.0]);
+
+        assert_eq!(summary.mean, 3.0);
+        assert_eq!(summary.median, 3.0);
+        assert_eq!(summary.p95, 5.0);
+        assert_eq!(Summary::new(&[]), Summary::default());
+    }
+
+    #[test]
+    fn test_bench() -> Result<()> {
+        let entries = parse_corpus("a 2 R U\na 3 F2 D L\nb 9 R U F D L B R U F\n")?;
+        let options = SolveOptions {
+            method: Method::Search(Strategy::IterativeDeepening),
+            max_depth: 3,
+        };
+        let mut reports = bench(&entries, &options, 2)?;
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!((reports[0].method.as_str(), reports[0].runs), ("search", 4));
+        assert_eq!(
+            (reports[1].method.as_str(), reports[1].runs),
+            ("thistlethwaite", 2)
+        );
+        assert!(
+            reports[1]
+                .to_string()
+                .starts_with("b (thistlethwaite): 2 runs")
+        );
+
+        let mut baseline = reports[0].clone();
+        baseline.nodes.median *= 2.0;
+        reports[0].compare(&[baseline]);
+        assert_eq!(reports[0].baseline.map(|b| b.nodes), Some(-0.5));
+
+        // measured times may not survive the decimal text of JSON exactly
+        reports[0].seconds = Summary {
+            mean: 0.5,
+            median: 0.25,
+            p95: 1.0,
+        };
+        let json = serde_json::to_string(&reports[0])?;
+        assert_eq!(serde_json::from_str::<GroupReport>(&json)?, reports[0]);
+
+        Ok(())
+    }
+}
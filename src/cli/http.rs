@@ -0,0 +1,226 @@
+use std::io::{self, BufRead, Read, Write};
+
+use serde::Serialize;
+
+/// Longest request line or header line accepted.
+const MAX_LINE: u64 = 8 * 1024;
+
+const MAX_HEADERS: usize = 64;
+
+/// Largest body accepted.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// HTTP/1.1 request. The query is split on `&` and `=` without decoding
+/// percent escapes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Reads a request, or returns the response rejecting it. Bodies are
+    /// read by their `Content-Length`, chunked bodies are not supported.
+    pub fn read(reader: &mut impl BufRead) -> Result<Self, Response> {
+        let line = read_line(reader)?;
+        let mut parts = line.split(' ');
+        let (Some(method), Some(target), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(Response::error(400, "invalid request line"));
+        };
+        if !version.starts_with("HTTP/1.") {
+            return Err(Response::error(505, "only HTTP/1.x is supported"));
+        }
+
+        let mut length = 0;
+        for i in 0.. {
+            let line = read_line(reader)?;
+            if line.is_empty() {
+                break;
+            }
+            if i == MAX_HEADERS {
+                return Err(Response::error(431, "too many headers"));
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(Response::error(400, "invalid header"));
+            };
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .parse()
+                    .map_err(|_| Response::error(400, "invalid Content-Length"))?;
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                return Err(Response::error(501, "Transfer-Encoding is not supported"));
+            }
+        }
+        if length > MAX_BODY {
+            return Err(Response::error(413, "body too large"));
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).map_err(read_error)?;
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (name.to_string(), value.to_string())
+            })
+            .collect();
+
+        Ok(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+            body,
+        })
+    }
+
+    /// Returns the value of the parameter of the query, if given.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads a line without its line ending.
+fn read_line(reader: &mut impl BufRead) -> Result<String, Response> {
+    let mut line = String::new();
+    reader
+        .take(MAX_LINE)
+        .read_line(&mut line)
+        .map_err(read_error)?;
+    if !line.ends_with('\n') {
+        return Err(Response::error(400, "incomplete or too long line"));
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_error(e: io::Error) -> Response {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            Response::error(408, "timed out reading the request")
+        }
+        io::ErrorKind::UnexpectedEof => Response::error(400, "incomplete request"),
+        _ => Response::error(400, &format!("cannot read the request: {e}")),
+    }
+}
+
+/// JSON response, after which the connection is closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+impl Response {
+    pub fn json(status: u16, body: &impl Serialize) -> Self {
+        match serde_json::to_string(body) {
+            Ok(body) => Self {
+                status,
+                headers: vec![],
+                body,
+            },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    /// Returns a response with the body `{"error": message}`.
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &ErrorBody { error: message })
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\n",
+            self.status,
+            reason(self.status)
+        )?;
+        write!(writer, "Content-Type: application/json\r\n")?;
+        write!(writer, "Content-Length: {}\r\n", self.body.len())?;
+        write!(writer, "Connection: close\r\n")?;
+        for (name, value) in &self.headers {
+            write!(writer, "{name}: {value}\r\n")?;
+        }
+        write!(writer, "\r\n{}", self.body)?;
+        writer.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Content Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        504 => "Gateway Timeout",
+        505 => "HTTP Version Not Supported",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request() {
+        let text = "POST /solve?depth=5&x HTTP/1.1\r\nHost: localhost\r\n\
+                    content-length: 4\r\n\r\nR U2";
+        let request = Request::read(&mut text.as_bytes()).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/solve");
+        assert_eq!(request.param("depth"), Some("5"));
+        assert_eq!(request.param("x"), Some(""));
+        assert_eq!(request.body, b"R U2");
+
+        let status = |text: &str| Request::read(&mut text.as_bytes()).unwrap_err().status;
+        assert_eq!(status("GET / HTTP/1.1\r\nContent-Length: 9\r\n\r\nR"), 400);
+        assert_eq!(status("GET /\r\n\r\n"), 400);
+        assert_eq!(status("GET / HTTP/2\r\n\r\n"), 505);
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: 2000000\r\n\r\n"),
+            413
+        );
+    }
+
+    #[test]
+    fn test_response() -> io::Result<()> {
+        let mut output = vec![];
+        Response::error(405, "no")
+            .with_header("Allow", "POST")
+            .write_to(&mut output)?;
+
+        assert_eq!(
+            String::from_utf8_lossy(&output),
+            "HTTP/1.1 405 Method Not Allowed\r\nContent-Type: application/json\r\n\
+             Content-Length: 14\r\nConnection: close\r\nAllow: POST\r\n\r\n{\"error\":\"no\"}"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Command line tool solving and scrambling cubes.
+//!
+//! Every command prints its reports as text, or with `--format json` as one
+//! JSON object per line, so that batches are written as JSON Lines. The
+//! interactive commands only print text.
+
+mod args;
+mod batch;
+mod bench;
+mod http;
+mod repl;
+mod scramble;
+mod serve;
+mod solve;
+mod terminal;
+mod tui;
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+
+use args::Args;
+
+const USAGE: &str = "\
+usage: rubiks-cube [--format text|json] <command> [options]
+
+commands:
+  solve <moves or facelets>  solve a scrambled cube
+      --method search|thistlethwaite  (default: search)
+      --strategy iddfs|bfs|astar|bidirectional  (default: iddfs)
+      --depth <n>  maximum number of moves searched (default: 7)
+  batch [file]  solve a scramble or facelet string per line of the file or
+      of stdin, writing the results in the order of the lines
+      --workers <n>  number of threads (default: number of CPUs)
+      and the options of solve
+  scramble  print a random scramble and the scrambled cube
+      --length <n>  number of moves (default: 25)
+  bench  solve the scrambles of a corpus, reporting the mean, median and
+      95th percentile of the time and of the nodes of each group
+      --corpus <file>  lines of a group, a length and a scramble
+          (default: the corpus in bench/corpus.txt)
+      --repeat <n>  runs per scramble (default: 1)
+      --save <file>  write the results as a baseline
+      --baseline <file>  compare the medians with a saved baseline
+      and the options of solve; the groups with scrambles longer than
+      --depth are solved with thistlethwaite
+  repl  turn the cube interactively, printing it after each line; type
+      help in the REPL for its commands
+      --session <file>  file keeping the moves, saved moves and history
+          (default: ~/.rubiks-cube-session.json)
+      and the options of solve; only prints text
+  tui  turn the cube with the keyboard in a full screen simulator, timing
+      the solves of scrambles; only prints text
+      --length <n>  number of moves of the scrambles (default: 25)
+  serve  answer JSON requests over HTTP on localhost:
+      POST /solve  solve the cube of a body with the fields cube, a
+          scramble or facelets, and method, strategy and depth defaulting
+          to the options of serve, the depth being at most that of serve
+      GET /scramble?length=<n>  a random scramble of n moves (default: 25)
+      POST /render  an SVG of the cube of a body with the fields cube,
+          view (net, top-layer or isometric) and colors
+      --port <n>  (default: 7878)
+      --timeout <ms>  time to answer a request, after which the search is
+          cancelled (default: 10000)
+      --workers <n>  requests handled at a time (default: number of CPUs)
+      and the options of solve
+  help  print this message
+
+Runs the bench when no command is given.
+";
+
+/// Format of the reports printed by the commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// A JSON object per line
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(anyhow!("invalid format: {s}")),
+        }
+    }
+}
+
+/// Prints the report in the format.
+pub fn emit<T: Serialize + Display>(format: Format, report: &T) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    match format {
+        Format::Text => write!(stdout, "{report}")?,
+        Format::Json => writeln!(stdout, "{}", serde_json::to_string(report)?)?,
+    }
+    Ok(())
+}
+
+/// Fails unless the format is text, for the commands which only print text.
+fn text_only(command: &str, format: Format) -> Result<()> {
+    match format {
+        Format::Text => Ok(()),
+        Format::Json => Err(anyhow!("{command} does not support --format json")),
+    }
+}
+
+/// Runs the command given by the arguments, without the program name.
+pub fn run(args: Vec<String>) -> Result<()> {
+    let args = Args::parse(args)?;
+    let format = args.value("format")?.unwrap_or_default();
+
+    match args.command() {
+        Some("solve") => solve::run(&args, format),
+        Some("batch") => batch::run(&args, format),
+        Some("scramble") => scramble::run(&args, format),
+        Some("repl") => {
+            text_only("repl", format)?;
+            repl::run(&args)
+        }
+        Some("tui") => {
+            text_only("tui", format)?;
+            tui::run(&args)
+        }
+        Some("serve") => serve::run(&args),
+        Some("bench") | None => bench::run(&args, format),
+        Some("help") => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        Some(command) => Err(anyhow!("unknown command: {command}\n\n{USAGE}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_only() {
+        for command in ["repl", "tui"] {
+            let args = ["--format", "json", command].map(String::from).to_vec();
+
+            assert_eq!(
+                run(args).unwrap_err().to_string(),
+                format!("{command} does not support --format json")
+            );
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+use rubiks_cube::{ColorScheme, Cube, Operation};
+use serde::{Deserialize, Serialize};
+
+use super::args::Args;
+use super::solve::{SolveOptions, format_operations};
+
+const HELP: &str = "\
+<moves>      apply moves, e.g. R U R'
+undo         revert the last command changing the cube
+reset        go back to the solved cube
+solve        print a solution of the cube
+invert       replace the moves by their inverse
+order        print how many times the moves must be repeated to solve
+save <name>  remember the moves under the name
+load <name>  restore the moves saved under the name
+saved        list the saved names
+history      print the lines entered in the sessions
+help         print this message
+quit         leave
+";
+
+/// Number of lines of history kept in the session file.
+const MAX_HISTORY: usize = 1000;
+
+/// State of the REPL kept between sessions: the moves applied to the
+/// solved cube, the saved moves and the lines entered.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub moves: Vec<Operation>,
+    pub saved: BTreeMap<String, Vec<Operation>>,
+    pub history: Vec<String>,
+    /// Moves before each command changing them, for undo
+    #[serde(skip)]
+    undo: Vec<Vec<Operation>>,
+}
+
+/// What the REPL should do after a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Print the message, if any, and the cube
+    Render(Option<String>),
+    Print(String),
+    Quit,
+}
+
+impl Session {
+    pub fn cube(&self) -> Cube {
+        let mut cube = Cube::new();
+        for operation in &self.moves {
+            cube.apply_operation(operation);
+        }
+        cube
+    }
+
+    fn set_moves(&mut self, moves: Vec<Operation>) {
+        let previous = std::mem::replace(&mut self.moves, moves);
+        self.undo.push(previous);
+    }
+
+    /// Runs a line of input.
+    pub fn execute(&mut self, line: &str, options: &SolveOptions) -> Result<Outcome> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(Outcome::Print(String::new()));
+        }
+        self.history.push(line.to_string());
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+        let outcome = match (command, argument) {
+            ("quit" | "exit", "") => Outcome::Quit,
+            ("help", "") => Outcome::Print(HELP.to_string()),
+            ("undo", "") => {
+                self.moves = self.undo.pop().ok_or_else(|| anyhow!("nothing to undo"))?;
+                Outcome::Render(None)
+            }
+            ("reset", "") => {
+                self.set_moves(vec![]);
+                Outcome::Render(None)
+            }
+            ("invert", "") => {
+                let inverse = self.moves.iter().rev().map(|op| op.inverse()).collect();
+                self.set_moves(inverse);
+                Outcome::Render(Some(format!("Moves: {}", format_operations(&self.moves))))
+            }
+            ("order", "") => Outcome::Print(format!("Order: {}\n", self.cube().order())),
+            ("solve", "") => {
+                let report = options.solve(&format_operations(&self.moves))?;
+                Outcome::Print(match report.solution {
+                    Some(solution) => format!("Solution: {}\n", format_operations(&solution)),
+                    None => format!("No solution within {} moves\n", report.max_depth),
+                })
+            }
+            ("save", name) if !name.is_empty() => {
+                self.saved.insert(name.to_string(), self.moves.clone());
+                Outcome::Print(format!("Saved {name}\n"))
+            }
+            ("load", name) if !name.is_empty() => {
+                let moves = self
+                    .saved
+                    .get(name)
+                    .ok_or_else(|| anyhow!("nothing saved as {name}"))?
+                    .clone();
+                self.set_moves(moves);
+                Outcome::Render(None)
+            }
+            ("saved", "") => Outcome::Print(
+                self.saved
+                    .iter()
+                    .map(|(name, moves)| format!("{name}: {}\n", format_operations(moves)))
+                    .collect(),
+            ),
+            ("history", "") => Outcome::Print(
+                self.history
+                    .iter()
+                    .map(|line| format!("{line}\n"))
+                    .collect(),
+            ),
+            _ => {
+                let operations = Operation::parse_sequence(line)?;
+                let mut moves = self.moves.clone();
+                moves.extend(operations);
+                self.set_moves(moves);
+                Outcome::Render(None)
+            }
+        };
+
+        Ok(outcome)
+    }
+}
+
+/// Returns the session file given by `--session`, or else the one in the
+/// home directory.
+fn session_path(args: &Args) -> Result<Option<PathBuf>> {
+    if let Some(path) = args.value::<String>("session")? {
+        return Ok(Some(PathBuf::from(path)));
+    }
+    Ok(std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".rubiks-cube-session.json")))
+}
+
+fn load_session(path: &Option<PathBuf>) -> Result<Session> {
+    let Some(path) = path else {
+        return Ok(Session::default());
+    };
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| anyhow!("invalid session {}: {e}", path.display())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Session::default()),
+        Err(e) => Err(anyhow!("cannot read {}: {e}", path.display())),
+    }
+}
+
+pub fn run(args: &Args) -> Result<()> {
+    let options = SolveOptions::from_args(args)?;
+    let path = session_path(args)?;
+    let mut session = load_session(&path)?;
+    let colors = io::stdout().is_terminal().then(ColorScheme::default);
+    let render = |session: &Session| {
+        let net = session.cube().net();
+        match colors {
+            Some(colors) => net.with_colors(colors).to_string(),
+            None => net.to_string(),
+        }
+    };
+
+    print!("{}", render(&session));
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            println!();
+            break;
+        };
+
+        match session.execute(&line?, &options) {
+            Ok(Outcome::Quit) => break,
+            Ok(Outcome::Print(message)) => print!("{message}"),
+            Ok(Outcome::Render(message)) => {
+                if let Some(message) = message {
+                    println!("{message}");
+                }
+                print!("{}", render(&session));
+            }
+            Err(e) => println!("error: {e}"),
+        }
+        if let Some(path) = &path {
+            fs::write(path, serde_json::to_string(&session)?)
+                .map_err(|e| anyhow!("cannot write {}: {e}", path.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::solve::Method;
+    use rubiks_cube::Strategy;
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let options = SolveOptions {
+            method: Method::Search(Strategy::IterativeDeepening),
+            max_depth: 4,
+        };
+        let mut session = Session::default();
+
+        session.execute("R U", &options)?;
+        session.execute("F'", &options)?;
+        assert_eq!(format_operations(&session.moves), "R U F'");
+        session.execute("undo", &options)?;
+        assert_eq!(format_operations(&session.moves), "R U");
+        assert_eq!(
+            session.execute("order", &options)?,
+            Outcome::Print("Order: 105\n".to_string())
+        );
+        assert_eq!(
+            session.execute("solve", &options)?,
+            Outcome::Print("Solution: U' R'\n".to_string())
+        );
+
+        session.execute("save sexy", &options)?;
+        session.execute("invert", &options)?;
+        assert_eq!(format_operations(&session.moves), "U' R'");
+        session.execute("reset", &options)?;
+        assert!(session.moves.is_empty());
+        session.execute("load sexy", &options)?;
+        assert_eq!(format_operations(&session.moves), "R U");
+
+        assert!(session.execute("load nothing", &options).is_err());
+        assert!(session.execute("R X", &options).is_err());
+        assert_eq!(session.execute("quit", &options)?, Outcome::Quit);
+        assert_eq!(session.history.len(), 12);
+
+        // the saved moves and the history outlive the session, but not undo
+        let json = serde_json::to_string(&session)?;
+        let mut restored: Session = serde_json::from_str(&json)?;
+        assert_eq!(restored.saved.len(), 1);
+        assert!(restored.execute("undo", &options).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+use rubiks_cube::{Cube, Operation};
+use serde::Serialize;
+
+use super::args::Args;
+use super::solve::format_operations;
+use super::{Format, emit};
+
+/// Random scramble and the cube it scrambles, written as its facelet string.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrambleReport {
+    pub scramble: Vec<Operation>,
+    pub cube: Cube,
+}
+
+impl Display for ScrambleReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Scramble: {}", format_operations(&self.scramble))?;
+        write!(f, "{}", self.cube)
+    }
+}
+
+pub fn run(args: &Args, format: Format) -> Result<()> {
+    let (cube, scramble) = Cube::random(args.value("length")?.unwrap_or(25));
+
+    emit(format, &ScrambleReport { scramble, cube })
+}
@@ -0,0 +1,379 @@
+use std::io::{self, BufReader, Read};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use rubiks_cube::{Cancellation, ColorScheme, Cube, Facelets, Svg};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::args::Args;
+use super::http::{Request, Response};
+use super::scramble::ScrambleReport;
+use super::solve::{Method, SolveOptions, parse_cube};
+
+/// Longest scramble returned by `GET /scramble`.
+const MAX_SCRAMBLE_LENGTH: usize = 1000;
+
+/// Body of `POST /solve`: the cube as a scramble or a facelet string, and
+/// the options overriding those of the service.
+#[derive(Debug, Deserialize)]
+struct SolveRequest {
+    cube: String,
+    method: Option<String>,
+    strategy: Option<String>,
+    depth: Option<usize>,
+}
+
+/// Body of `POST /render`: the cube, the view `net`, `top-layer` or
+/// `isometric`, and the colors as for [`ColorScheme`].
+#[derive(Debug, Deserialize)]
+struct RenderRequest {
+    cube: String,
+    view: Option<String>,
+    colors: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RenderResponse {
+    svg: String,
+}
+
+/// Handles the requests of the service. Requests must be answered within
+/// the timeout, after which searches are cancelled. The depth of a search
+/// is at most that of the options.
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub options: SolveOptions,
+    pub timeout: Duration,
+}
+
+impl Service {
+    /// Returns the time by which a request received at `received` must be
+    /// answered, or `None` if the timeout is too long to tell.
+    fn deadline(&self, received: Instant) -> Option<Instant> {
+        received.checked_add(self.timeout)
+    }
+
+    /// Answers a request received at `received`.
+    pub fn handle(&self, request: &Request, received: Instant) -> Response {
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/solve") => self.solve(request, received),
+            ("GET", "/scramble") => scramble(request),
+            ("POST", "/render") => render(request),
+            (_, "/solve" | "/render") => {
+                return Response::error(405, "method not allowed").with_header("Allow", "POST");
+            }
+            (_, "/scramble") => {
+                return Response::error(405, "method not allowed").with_header("Allow", "GET");
+            }
+            (_, path) => return Response::error(404, &format!("not found: {path}")),
+        };
+
+        result.unwrap_or_else(|e| Response::error(400, &e.to_string()))
+    }
+
+    fn solve(&self, request: &Request, received: Instant) -> Result<Response> {
+        let body: SolveRequest = parse_body(request)?;
+        if let Some(depth) = body.depth
+            && depth > self.options.max_depth
+        {
+            return Err(anyhow!("depth above {}", self.options.max_depth));
+        }
+        let options = SolveOptions {
+            method: match (&body.method, &body.strategy) {
+                (None, None) => self.options.method,
+                (method, strategy) => Method::parse(method.as_deref(), strategy.as_deref())?,
+            },
+            max_depth: body.depth.unwrap_or(self.options.max_depth),
+        };
+
+        let cancellation = match self.deadline(received) {
+            Some(deadline) => Cancellation::new().with_deadline(deadline),
+            None => Cancellation::new(),
+        };
+        match options.solve_with_cancellation(&body.cube, &cancellation) {
+            Ok(report) => Ok(Response::json(200, &report)),
+            Err(_) if cancellation.is_cancelled() => Ok(Response::error(
+                504,
+                &format!("no solution found within {} ms", self.timeout.as_millis()),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(request: &Request) -> Result<T> {
+    serde_json::from_slice(&request.body).map_err(|e| anyhow!("invalid body: {e}"))
+}
+
+fn scramble(request: &Request) -> Result<Response> {
+    let length = match request.param("length") {
+        Some(length) => length
+            .parse()
+            .map_err(|_| anyhow!("invalid length: {length}"))?,
+        None => 25,
+    };
+    if length > MAX_SCRAMBLE_LENGTH {
+        return Err(anyhow!("length above {MAX_SCRAMBLE_LENGTH}"));
+    }
+
+    let (cube, scramble) = Cube::random(length);
+    Ok(Response::json(200, &ScrambleReport { scramble, cube }))
+}
+
+fn render(request: &Request) -> Result<Response> {
+    let body: RenderRequest = parse_body(request)?;
+    let (_, cube) = parse_cube(&body.cube)?;
+    let mut svg = Svg::new(Facelets::from_rotation(&cube.rotation));
+    if let Some(colors) = &body.colors {
+        svg = svg.with_colors(colors.parse::<ColorScheme>()?);
+    }
+    let svg = match body.view.as_deref() {
+        None | Some("net") => svg.net(),
+        Some("top-layer") => svg.top_layer(),
+        Some("isometric") => svg.isometric(),
+        Some(view) => return Err(anyhow!("invalid view: {view}")),
+    };
+
+    Ok(Response::json(200, &RenderResponse { svg }))
+}
+
+/// Reader of a connection failing with [`io::ErrorKind::TimedOut`] once the
+/// deadline, if any, is passed, however the bytes are spread over time.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Option<Instant>,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+            self.stream.set_read_timeout(Some(remaining))?;
+        }
+        self.stream.read(buffer)
+    }
+}
+
+/// Reads a request from the connection and writes the response, logging
+/// it to stderr.
+fn handle_connection(stream: TcpStream, service: &Service) {
+    let received = Instant::now();
+    // a client too slow to send its request is answered with 408
+    let mut reader = BufReader::new(DeadlineReader {
+        stream: &stream,
+        deadline: service.deadline(received),
+    });
+    let (request, response) = match Request::read(&mut reader) {
+        Ok(request) => {
+            let response = service.handle(&request, received);
+            (format!("{} {}", request.method, request.path), response)
+        }
+        Err(response) => ("-".to_string(), response),
+    };
+
+    let _ = response.write_to(&mut &stream);
+    eprintln!(
+        "{request} {} {:.1} ms",
+        response.status,
+        received.elapsed().as_secs_f64() * 1e3
+    );
+}
+
+/// Answers the connections of the listener with `workers` threads, each
+/// handling a connection at a time.
+pub fn serve(listener: TcpListener, service: Service, workers: usize) -> Result<()> {
+    let (sender, connections) = mpsc::sync_channel::<TcpStream>(workers.max(1) * 4);
+    let connections = Arc::new(Mutex::new(connections));
+
+    thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            let connections = Arc::clone(&connections);
+            let service = &service;
+            scope.spawn(move || {
+                loop {
+                    let connection = connections.lock().unwrap().recv();
+                    let Ok(stream) = connection else {
+                        break;
+                    };
+                    handle_connection(stream, service);
+                }
+            });
+        }
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => sender.send(stream)?,
+                Err(e) => eprintln!("cannot accept a connection: {e}"),
+            }
+        }
+        Ok(())
+    })
+}
+
+pub fn run(args: &Args) -> Result<()> {
+    let service = Service {
+        options: SolveOptions::from_args(args)?,
+        timeout: Duration::from_millis(args.value("timeout")?.unwrap_or(10_000)),
+    };
+    let workers = match args.value("workers")? {
+        Some(workers) => workers,
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let port = args.value("port")?.unwrap_or(7878);
+
+    // load the tables of Thistlethwaite's method, which any request can
+    // ask for and which are not generated within the timeout of a request
+    let thistlethwaite = SolveOptions {
+        method: Method::Thistlethwaite,
+        ..service.options.clone()
+    };
+    thistlethwaite.solve("")?;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| anyhow!("cannot listen on port {port}: {e}"))?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    serve(listener, service, workers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rubiks_cube::Strategy;
+    use std::io::{Read, Write};
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query
+                .split_once('=')
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .into_iter()
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn service(timeout: Duration) -> Service {
+        Service {
+            options: SolveOptions {
+                method: Method::Search(Strategy::IterativeDeepening),
+                max_depth: 4,
+            },
+            timeout,
+        }
+    }
+
+    #[test]
+    fn test_handle() -> Result<()> {
+        let service = service(Duration::from_secs(60));
+        let handle =
+            |method, path, body| service.handle(&request(method, path, body), Instant::now());
+
+        let response = handle("POST", "/solve", r#"{"cube": "R U2"}"#);
+        let json: serde_json::Value = serde_json::from_str(&response.body)?;
+        assert_eq!(response.status, 200);
+        assert_eq!(json["solution"], serde_json::json!(["U2", "R'"]));
+        let response = handle("POST", "/solve", r#"{"cube": "R U", "method": "x"}"#);
+        assert_eq!(response.body, r#"{"error":"invalid method: x"}"#);
+        let response = handle("POST", "/solve", r#"{"cube": "R U", "depth": 5}"#);
+        assert_eq!(response.body, r#"{"error":"depth above 4"}"#);
+        assert_eq!(handle("POST", "/solve", "R U").status, 400);
+
+        let response = handle("GET", "/scramble?length=7", "");
+        let json: serde_json::Value = serde_json::from_str(&response.body)?;
+        assert_eq!(json["scramble"].as_array().map(Vec::len), Some(7));
+        assert_eq!(handle("GET", "/scramble?length=1001", "").status, 400);
+
+        let response = handle("POST", "/render", r#"{"cube": "F", "view": "isometric"}"#);
+        let json: serde_json::Value = serde_json::from_str(&response.body)?;
+        assert!(json["svg"].as_str().unwrap().starts_with("<svg"));
+        assert_eq!(
+            handle("POST", "/render", r#"{"cube": "F", "view": "3d"}"#).status,
+            400
+        );
+
+        let response = handle("GET", "/solve", "");
+        assert_eq!(response.status, 405);
+        assert_eq!(response.headers, [("Allow", "POST".to_string())]);
+        assert_eq!(handle("GET", "/", "").status, 404);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_timeout() {
+        let body = r#"{"cube": "R U F D L B R", "depth": 4}"#;
+        let handle =
+            |timeout| service(timeout).handle(&request("POST", "/solve", body), Instant::now());
+
+        assert_eq!(handle(Duration::ZERO).status, 504);
+        // a timeout too long for a deadline does not overflow
+        assert_eq!(handle(Duration::MAX).status, 200);
+    }
+
+    #[test]
+    fn test_serve() -> Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let address = listener.local_addr()?;
+        thread::spawn(move || serve(listener, service(Duration::MAX), 2));
+
+        let clients = (0..4).map(|_| {
+            thread::spawn(move || -> Result<String> {
+                let mut stream = TcpStream::connect(address)?;
+                let body = r#"{"cube": "F2 D'"}"#;
+                write!(
+                    stream,
+                    "POST /solve HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                )?;
+                let mut response = String::new();
+                stream.read_to_string(&mut response)?;
+                Ok(response)
+            })
+        });
+        for client in clients.collect::<Vec<_>>() {
+            let response = client.join().unwrap()?;
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+            assert!(response.contains(r#""solution":["D","F2"]"#));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serve_slow_client() -> Result<()> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let address = listener.local_addr()?;
+        thread::spawn(move || serve(listener, service(Duration::from_millis(300)), 1));
+
+        // each byte comes well within the timeout, but not the request,
+        // which would take 2 s
+        let mut stream = TcpStream::connect(address)?;
+        let mut writer = stream.try_clone()?;
+        let start = Instant::now();
+        thread::spawn(move || {
+            for byte in b"GET /scramble HTTP/1.1\r\nHost: localhost\r\n\r\n" {
+                if writer.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+        });
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        Ok(())
+    }
+}
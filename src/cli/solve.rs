@@ -0,0 +1,234 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
+use rubiks_cube::{
+    Cancellation, Cube, Facelets, Operation, Solver, Strategy, solve_thistlethwaite_with_stats,
+};
+use serde::Serialize;
+
+use super::args::Args;
+use super::{Format, emit};
+
+/// Solving method of the `solve` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// The shortest solution searched by [`Solver`]
+    Search(Strategy),
+    Thistlethwaite,
+}
+
+/// How to solve the cubes given to the commands.
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    pub method: Method,
+    pub max_depth: usize,
+}
+
+impl Method {
+    /// Parses the names of a method and of the strategy of the search, the
+    /// defaults being iterative deepening.
+    pub fn parse(method: Option<&str>, strategy: Option<&str>) -> Result<Self> {
+        let strategy = match strategy {
+            None | Some("iddfs") => Strategy::IterativeDeepening,
+            Some("bfs") => Strategy::BreadthFirst,
+            Some("astar") => Strategy::AStar,
+            Some("bidirectional") => Strategy::Bidirectional,
+            Some(strategy) => return Err(anyhow!("invalid strategy: {strategy}")),
+        };
+        match method {
+            None | Some("search") => Ok(Method::Search(strategy)),
+            Some("thistlethwaite") => Ok(Method::Thistlethwaite),
+            Some(method) => Err(anyhow!("invalid method: {method}")),
+        }
+    }
+
+    /// Returns the name of the method as given to `--method`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::Search(_) => "search",
+            Method::Thistlethwaite => "thistlethwaite",
+        }
+    }
+}
+
+impl SolveOptions {
+    pub fn from_args(args: &Args) -> Result<Self> {
+        Ok(Self {
+            method: Method::parse(
+                args.value::<String>("method")?.as_deref(),
+                args.value::<String>("strategy")?.as_deref(),
+            )?,
+            max_depth: args.value("depth")?.unwrap_or(7),
+        })
+    }
+
+    /// Solves the cube given by `input`, a scramble or a facelet string.
+    pub fn solve(&self, input: &str) -> Result<SolveReport> {
+        self.solve_with_cancellation(input, &Cancellation::new())
+    }
+
+    /// Solves as [`SolveOptions::solve`], stopping the search when
+    /// cancelled. Thistlethwaite's method runs to the end.
+    pub fn solve_with_cancellation(
+        &self,
+        input: &str,
+        cancellation: &Cancellation,
+    ) -> Result<SolveReport> {
+        let (scramble, cube) = parse_cube(input)?;
+
+        let start = Instant::now();
+        let (solution, nodes) = match self.method {
+            Method::Search(strategy) => {
+                let (solution, stats) = Solver::new(self.max_depth)
+                    .with_strategy(strategy)
+                    .with_cancellation(cancellation.clone())
+                    .solve_with_stats(&cube)?;
+                (solution, stats.nodes)
+            }
+            Method::Thistlethwaite => {
+                let (solution, stats) = solve_thistlethwaite_with_stats(&cube)?;
+                (Some(solution.operations()), stats.nodes)
+            }
+        };
+        let seconds = start.elapsed().as_secs_f64();
+
+        Ok(SolveReport {
+            scramble,
+            cube,
+            moves: solution.as_deref().map(MoveCount::new),
+            solution,
+            max_depth: self.max_depth,
+            nodes,
+            seconds,
+        })
+    }
+}
+
+/// Parses a facelet string or a scramble applied to the solved cube,
+/// returning the scramble if any.
+pub fn parse_cube(input: &str) -> Result<(Option<Vec<Operation>>, Cube)> {
+    let input = input.trim();
+    if input.len() == 54 && !input.contains(char::is_whitespace) {
+        let rotation = Facelets::from_str(input)?.to_rotation()?;
+        return Ok((None, Cube { rotation }));
+    }
+
+    let scramble = Operation::parse_sequence(input)?;
+    let mut cube = Cube::new();
+    for operation in &scramble {
+        cube.apply_operation(operation);
+    }
+    Ok((Some(scramble), cube))
+}
+
+pub fn format_operations(operations: &[Operation]) -> String {
+    operations
+        .iter()
+        .map(|op| op.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Length of a solution in the half turn and the quarter turn metrics.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MoveCount {
+    pub htm: usize,
+    pub qtm: usize,
+}
+
+impl MoveCount {
+    pub fn new(operations: &[Operation]) -> Self {
+        Self {
+            htm: operations.len(),
+            qtm: operations.iter().map(|op| op.quarter_turns()).sum(),
+        }
+    }
+}
+
+/// Result of solving a cube. The cube is written as its facelet string.
+#[derive(Debug, Clone, Serialize)]
+pub struct SolveReport {
+    pub scramble: Option<Vec<Operation>>,
+    pub cube: Cube,
+    pub solution: Option<Vec<Operation>>,
+    pub moves: Option<MoveCount>,
+    #[serde(skip)]
+    pub max_depth: usize,
+    /// Number of states expanded by the search
+    pub nodes: u64,
+    pub seconds: f64,
+}
+
+impl Display for SolveReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.scramble {
+            Some(scramble) => writeln!(f, "Scramble: {}", format_operations(scramble))?,
+            None => writeln!(f, "Cube: {}", Facelets::from_rotation(&self.cube.rotation))?,
+        }
+        match (&self.solution, &self.moves) {
+            (Some(solution), Some(moves)) => writeln!(
+                f,
+                "Solution: {} ({} HTM, {} QTM)",
+                format_operations(solution),
+                moves.htm,
+                moves.qtm
+            )?,
+            _ => writeln!(f, "Solution: not found within {} moves", self.max_depth)?,
+        }
+        writeln!(f, "Nodes: {}", self.nodes)?;
+        writeln!(f, "Time: {:.2} secs", self.seconds)
+    }
+}
+
+pub fn run(args: &Args, format: Format) -> Result<()> {
+    let options = SolveOptions::from_args(args)?;
+    let input = args.operands().join(" ");
+    if input.trim().is_empty() {
+        return Err(anyhow!("missing the scramble or facelets to solve"));
+    }
+
+    emit(format, &options.solve(&input)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cube() -> Result<()> {
+        let (scramble, cube) = parse_cube("R U' F2")?;
+        let facelets = Facelets::from_rotation(&cube.rotation).to_string();
+        let (none, same) = parse_cube(&facelets)?;
+
+        assert_eq!(scramble.map(|s| s.len()), Some(3));
+        assert_eq!(none, None);
+        assert_eq!(same, cube);
+        assert!(parse_cube("R X").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_report() -> Result<()> {
+        let options = SolveOptions {
+            method: Method::Search(Strategy::IterativeDeepening),
+            max_depth: 3,
+        };
+        let report = options.solve("R U2")?;
+        let json: serde_json::Value = serde_json::to_value(&report)?;
+
+        assert_eq!(json["solution"], serde_json::json!(["U2", "R'"]));
+        assert_eq!(json["moves"], serde_json::json!({"htm": 2, "qtm": 3}));
+        assert!(json["nodes"].as_u64().unwrap() > 0);
+        assert_eq!(json["cube"].as_str().map(str::len), Some(54));
+        assert!(
+            report
+                .to_string()
+                .contains("Solution: U2 R' (2 HTM, 3 QTM)")
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{Result, anyhow};
+
+/// Puts the terminal in raw mode on the alternate screen, without echo nor
+/// cursor, and restores it when dropped, panics included. The modes are
+/// set with `stty`, which changes the terminal of its standard input.
+pub struct RawMode {
+    /// Settings of the terminal before, as printed by `stty -g`
+    saved: String,
+}
+
+impl RawMode {
+    pub fn enable() -> Result<Self> {
+        let saved = stty(&["-g"])?.trim().to_string();
+        stty(&["raw", "-echo"])?;
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b[?1049h\x1b[?25l")?;
+        stdout.flush()?;
+
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1b[?25h\x1b[?1049l");
+        let _ = stdout.flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .map_err(|e| anyhow!("cannot run stty: {e}"))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "stty failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
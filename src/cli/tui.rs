@@ -0,0 +1,294 @@
+use std::io::{self, IsTerminal, Read, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use rubiks_cube::{ColorScheme, Cube, Operation};
+
+use super::args::Args;
+use super::solve::format_operations;
+use super::terminal::RawMode;
+
+const KEYS: &str = "\
+u r f d l b  turn a face clockwise, with shift counterclockwise
+space        scramble      backspace  undo
+enter        reset         q          quit";
+
+/// Number of the last moves shown.
+const LAST_MOVES: usize = 12;
+
+/// Interval between redraws updating the timer.
+const TICK: Duration = Duration::from_millis(100);
+
+const ESCAPE: u8 = 0x1b;
+
+/// Returns the length of the escape sequence at the start of `bytes`: a
+/// control sequence `ESC [` ended by a byte in `@..=~`, as sent by the arrow
+/// keys, `ESC O` and a byte, or `ESC` and a byte, as sent with alt.
+fn escape_length(bytes: &[u8]) -> usize {
+    let length = match bytes.get(1) {
+        None => 1,
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |i| i + 3),
+        Some(b'O') => 3,
+        Some(_) => 2,
+    };
+    length.min(bytes.len())
+}
+
+/// Returns the face turn of a key: the lowercase letter of a face turns it
+/// clockwise, and the uppercase letter counterclockwise.
+fn turn(key: u8) -> Option<Operation> {
+    use Operation::*;
+    Some(match key {
+        b'u' => U,
+        b'U' => U3,
+        b'r' => R,
+        b'R' => R3,
+        b'f' => F,
+        b'F' => F3,
+        b'd' => D,
+        b'D' => D3,
+        b'l' => L,
+        b'L' => L3,
+        b'b' => B,
+        b'B' => B3,
+        _ => return None,
+    })
+}
+
+/// State of the simulator. The timer starts with the first turn after a
+/// scramble or a reset, and stops when the cube is solved. Turning the cube
+/// again after a solve starts the timer over.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    cube: Cube,
+    scramble: Option<Vec<Operation>>,
+    scramble_length: usize,
+    moves: Vec<Operation>,
+    started: Option<Instant>,
+    /// Time taken to solve the cube, once solved
+    solved: Option<Duration>,
+}
+
+impl Simulator {
+    pub fn new(scramble_length: usize) -> Self {
+        Self {
+            cube: Cube::new(),
+            scramble: None,
+            scramble_length,
+            moves: vec![],
+            started: None,
+            solved: None,
+        }
+    }
+
+    fn restart(&mut self, cube: Cube, scramble: Option<Vec<Operation>>) {
+        *self = Self {
+            cube,
+            scramble,
+            ..Self::new(self.scramble_length)
+        };
+    }
+
+    /// Handles the bytes read from the terminal at `now`, skipping the
+    /// escape sequences of keys such as the arrows, and returns false to
+    /// quit.
+    pub fn input(&mut self, bytes: &[u8], now: Instant) -> bool {
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == ESCAPE {
+                i += escape_length(&bytes[i..]);
+            } else if self.key(bytes[i], now) {
+                i += 1;
+            } else {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Handles a key pressed at `now`, returning false to quit.
+    pub fn key(&mut self, key: u8, now: Instant) -> bool {
+        match key {
+            // ctrl-c is a key in raw mode
+            b'q' | 3 => return false,
+            b' ' => {
+                let (cube, scramble) = Cube::random(self.scramble_length);
+                self.restart(cube, Some(scramble));
+            }
+            b'\r' | b'\n' => self.restart(Cube::new(), None),
+            // backspace and delete
+            8 | 127 => {
+                if let Some(operation) = self.moves.pop() {
+                    self.cube.apply_operation(&operation.inverse());
+                    self.update_timer(now);
+                }
+            }
+            key => {
+                if let Some(operation) = turn(key) {
+                    self.cube.apply_operation(&operation);
+                    self.moves.push(operation);
+                    self.update_timer(now);
+                }
+            }
+        }
+        true
+    }
+
+    /// Starts or stops the timer after the cube was turned at `now`.
+    fn update_timer(&mut self, now: Instant) {
+        if self.solved.take().is_some() {
+            self.started = Some(now);
+        }
+        let started = *self.started.get_or_insert(now);
+        if self.cube == Cube::new() {
+            self.solved = Some(now - started);
+        }
+    }
+
+    /// Returns the time on the timer at `now`.
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        match (self.started, self.solved) {
+            (_, Some(solved)) => solved,
+            (Some(started), None) => now - started,
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    /// Draws the screen at `now`, for a terminal in raw mode.
+    pub fn frame(&self, now: Instant, colors: ColorScheme) -> String {
+        let mut lines = vec!["Rubik's cube".to_string(), String::new()];
+        lines.extend(
+            self.cube
+                .net()
+                .with_colors(colors)
+                .to_string()
+                .lines()
+                .map(str::to_string),
+        );
+        lines.push(String::new());
+        lines.push(format!(
+            "Moves: {}   Time: {:.1} s{}",
+            self.moves.len(),
+            self.elapsed(now).as_secs_f64(),
+            if self.solved.is_some() {
+                "   Solved!"
+            } else {
+                ""
+            }
+        ));
+        let last = &self.moves[self.moves.len().saturating_sub(LAST_MOVES)..];
+        lines.push(format!("Last moves: {}", format_operations(last)));
+        if let Some(scramble) = &self.scramble {
+            lines.push(format!("Scramble: {}", format_operations(scramble)));
+        }
+        lines.push(String::new());
+        lines.extend(KEYS.lines().map(str::to_string));
+
+        // draw over the previous frame, clearing what is left of it
+        let mut frame = "\x1b[H".to_string();
+        for line in lines {
+            frame += &line;
+            frame += "\x1b[K\r\n";
+        }
+        frame + "\x1b[J"
+    }
+}
+
+pub fn run(args: &Args) -> Result<()> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(anyhow!("the tui needs a terminal"));
+    }
+    let mut simulator = Simulator::new(args.value("length")?.unwrap_or(25));
+    let colors = ColorScheme::default();
+
+    let _raw_mode = RawMode::enable()?;
+    // the reader blocks on stdin until the process exits
+    let (sender, keys) = mpsc::channel();
+    thread::spawn(move || {
+        // the bytes of a read are sent together to keep escape sequences
+        // whole
+        let mut buffer = [0; 64];
+        while let Ok(n @ 1..) = io::stdin().read(&mut buffer) {
+            if sender.send(buffer[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut stdout = io::stdout();
+    loop {
+        write!(stdout, "{}", simulator.frame(Instant::now(), colors))?;
+        stdout.flush()?;
+        match keys.recv_timeout(TICK) {
+            Ok(bytes) => {
+                if !simulator.input(&bytes, Instant::now()) {
+                    break;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulator() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let mut simulator = Simulator::new(25);
+
+        assert_eq!(simulator.elapsed(start), Duration::ZERO);
+        for (i, &key) in b"ruUx".iter().enumerate() {
+            assert!(simulator.key(key, start + second * i as u32));
+        }
+        assert_eq!(format_operations(&simulator.moves), "R U U'");
+        assert_eq!(simulator.elapsed(start + second * 5), second * 5);
+
+        assert!(simulator.key(b'R', start + second * 6));
+        assert_eq!(simulator.cube, Cube::new());
+        assert_eq!(simulator.elapsed(start + second * 9), second * 6);
+        assert!(
+            simulator
+                .frame(start, ColorScheme::default())
+                .contains("Solved!")
+        );
+
+        assert!(simulator.key(127, start + second * 10));
+        assert_eq!(simulator.cube, Cube::new().operation_applied(&Operation::R));
+        assert_eq!(simulator.elapsed(start + second * 12), second * 2);
+        assert!(
+            !simulator
+                .frame(start, ColorScheme::default())
+                .contains("Solved!")
+        );
+
+        assert!(simulator.key(b' ', start));
+        assert!(simulator.moves.is_empty());
+        assert_eq!(simulator.scramble.as_ref().map(Vec::len), Some(25));
+        assert_eq!(simulator.elapsed(start + second), Duration::ZERO);
+        assert!(!simulator.key(b'q', start));
+    }
+
+    #[test]
+    fn test_input() {
+        let now = Instant::now();
+        let mut simulator = Simulator::new(25);
+
+        // down and up arrows, alt-u, then r and a lone escape
+        assert!(simulator.input(b"\x1b[B\x1bOA\x1bur\x1b", now));
+        assert_eq!(format_operations(&simulator.moves), "R");
+        assert!(simulator.input(b"\x1b[1;5C", now));
+        assert!(!simulator.input(b"\x1b[Aq", now));
+    }
+}
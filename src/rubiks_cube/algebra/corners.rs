@@ -0,0 +1,98 @@
+use std::ops::{Mul, MulAssign};
+
+use anyhow::{Result, anyhow};
+
+use super::quotient_group_vec::QuotientGroupVec;
+use super::symmetric_group::SymmetricGroup;
+
+/// Permutation and orientation of the 8 corners, shared by the cubes of
+/// all sizes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Corners {
+    perm: SymmetricGroup<8>,
+    orient: QuotientGroupVec<3, 8>,
+}
+
+impl Corners {
+    pub fn new(perm: SymmetricGroup<8>, orient: QuotientGroupVec<3, 8>) -> Self {
+        Self { perm, orient }
+    }
+
+    /// Builds the corners from the pieces at each position and their
+    /// orientations, failing if they are not a permutation and orientations.
+    pub fn try_from_parts(perm: [usize; 8], orient: [u8; 8]) -> Result<Self> {
+        if let Some(o) = orient.iter().find(|o| **o >= 3) {
+            return Err(anyhow!("invalid corner orientation: {o}"));
+        }
+
+        Ok(Self::new(perm.try_into()?, orient.into()))
+    }
+
+    /// Returns the corner piece placed at `position` and its orientation.
+    pub fn corner(&self, position: usize) -> (usize, u8) {
+        (self.perm[position], self.orient[position].into())
+    }
+
+    pub fn is_permutation_even(&self) -> bool {
+        self.perm.is_even()
+    }
+
+    /// Returns true if the orientations sum to zero, as in every state
+    /// reachable by operations.
+    pub fn is_orientation_solvable(&self) -> bool {
+        (0..8).map(|i| self.corner(i).1 as usize).sum::<usize>() % 3 == 0
+    }
+
+    pub fn hamming_distance(&self, other: &Self) -> usize {
+        (0..8usize)
+            .filter(|i| self.perm[*i] != other.perm[*i])
+            .filter(|i| self.orient[*i] != other.orient[*i])
+            .count()
+    }
+}
+
+impl Mul for &Corners {
+    type Output = Corners;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let perm_orient = rhs.perm.permute(&self.orient.into()).into();
+
+        Corners::new(&self.perm * &rhs.perm, &perm_orient + &rhs.orient)
+    }
+}
+
+impl MulAssign<&Corners> for Corners {
+    fn mul_assign(&mut self, rhs: &Corners) {
+        self.perm *= &rhs.perm;
+
+        self.orient = rhs.perm.permute(&self.orient.into()).into();
+        self.orient += rhs.orient;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_parts() {
+        assert!(Corners::try_from_parts([0, 1, 2, 3, 4, 5, 6, 7], [0; 8]).is_ok());
+        assert!(Corners::try_from_parts([0, 0, 2, 3, 4, 5, 6, 7], [0; 8]).is_err());
+        assert!(
+            Corners::try_from_parts([0, 1, 2, 3, 4, 5, 6, 7], [3, 0, 0, 0, 0, 0, 0, 0]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_mul() {
+        let twist =
+            Corners::try_from_parts([1, 0, 2, 3, 4, 5, 6, 7], [1, 2, 0, 0, 0, 0, 0, 0]).unwrap();
+        let mut result = Corners::default();
+        result *= &twist;
+
+        assert_eq!(result, &Corners::default() * &twist);
+        assert_eq!(&twist * &twist, Corners::default());
+        assert!(!twist.is_permutation_even());
+        assert!(twist.is_orientation_solvable());
+    }
+}
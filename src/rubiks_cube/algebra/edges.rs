@@ -0,0 +1,86 @@
+use std::ops::{Mul, MulAssign};
+
+use anyhow::{Result, anyhow};
+
+use super::quotient_group_vec::QuotientGroupVec;
+use super::symmetric_group::SymmetricGroup;
+
+/// Permutation and orientation of the 12 edges of the 3x3x3 cube, which
+/// are the middle edges of the odd cubes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Edges {
+    perm: SymmetricGroup<12>,
+    orient: QuotientGroupVec<2, 12>,
+}
+
+impl Edges {
+    pub fn new(perm: SymmetricGroup<12>, orient: QuotientGroupVec<2, 12>) -> Self {
+        Self { perm, orient }
+    }
+
+    /// Builds the edges from the pieces at each position and their
+    /// orientations, failing if they are not a permutation and orientations.
+    pub fn try_from_parts(perm: [usize; 12], orient: [u8; 12]) -> Result<Self> {
+        if let Some(o) = orient.iter().find(|o| **o >= 2) {
+            return Err(anyhow!("invalid edge orientation: {o}"));
+        }
+
+        Ok(Self::new(perm.try_into()?, orient.into()))
+    }
+
+    /// Returns the edge piece placed at `position` and its orientation.
+    pub fn edge(&self, position: usize) -> (usize, u8) {
+        (self.perm[position], self.orient[position].into())
+    }
+
+    pub fn is_permutation_even(&self) -> bool {
+        self.perm.is_even()
+    }
+
+    /// Returns true if the orientations sum to zero, as in every state
+    /// reachable by operations.
+    pub fn is_orientation_solvable(&self) -> bool {
+        (0..12).map(|i| self.edge(i).1 as usize).sum::<usize>() % 2 == 0
+    }
+
+    pub fn hamming_distance(&self, other: &Self) -> usize {
+        (0..12usize)
+            .filter(|i| self.perm[*i] != other.perm[*i])
+            .filter(|i| self.orient[*i] != other.orient[*i])
+            .count()
+    }
+}
+
+impl Mul for &Edges {
+    type Output = Edges;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let perm_orient = rhs.perm.permute(&self.orient.into()).into();
+
+        Edges::new(&self.perm * &rhs.perm, &perm_orient + &rhs.orient)
+    }
+}
+
+impl MulAssign<&Edges> for Edges {
+    fn mul_assign(&mut self, rhs: &Edges) {
+        self.perm *= &rhs.perm;
+
+        self.orient = rhs.perm.permute(&self.orient.into()).into();
+        self.orient += rhs.orient;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul() {
+        let flip = Edges::try_from_parts([1, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11], [1; 12]).unwrap();
+
+        assert_eq!(&flip * &flip, Edges::default());
+        assert!(!flip.is_permutation_even());
+        assert!(flip.is_orientation_solvable());
+        assert!(Edges::try_from_parts([0; 12], [0; 12]).is_err());
+    }
+}
@@ -1,4 +1,7 @@
+pub mod corners;
+pub mod edges;
 pub mod quotient_group;
 pub mod quotient_group_vec;
 pub mod rotation;
+pub mod stabilizer_chain;
 pub mod symmetric_group;
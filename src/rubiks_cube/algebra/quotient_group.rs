@@ -8,7 +8,7 @@ use std::{
 /// This type implements the mathematical quotient group structure where elements
 /// are equivalence classes of integers under modulo N arithmetic. All operations
 /// maintain the invariant that the internal value is always in the range [0, N).
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct QuotientGroup<const N: u8> {
     value: u8,
 }
@@ -7,7 +7,7 @@ use super::quotient_group::QuotientGroup;
 
 /// A fixed-size vector of quotient group elements.
 /// Represents a vector of `M` elements from the quotient group Z/NZ.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct QuotientGroupVec<const N: u8, const M: usize> {
     value: [QuotientGroup<N>; M],
 }
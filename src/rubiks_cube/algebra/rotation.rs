@@ -1,14 +1,16 @@
 use std::ops::{Mul, MulAssign};
 
+use anyhow::{Result, anyhow};
+
+use super::corners::Corners;
+use super::edges::Edges;
 use super::quotient_group_vec::QuotientGroupVec;
 use super::symmetric_group::SymmetricGroup;
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Rotation {
-    edge_perm: SymmetricGroup<12>,
-    edge_orient: QuotientGroupVec<2, 12>,
-    corner_perm: SymmetricGroup<8>,
-    corner_orient: QuotientGroupVec<3, 8>,
+    edges: Edges,
+    corners: Corners,
 }
 
 impl Rotation {
@@ -19,25 +21,110 @@ impl Rotation {
         corner_orient: QuotientGroupVec<3, 8>,
     ) -> Self {
         Self {
-            edge_perm,
-            edge_orient,
-            corner_perm,
-            corner_orient,
+            edges: Edges::new(edge_perm, edge_orient),
+            corners: Corners::new(corner_perm, corner_orient),
+        }
+    }
+
+    /// Builds the rotation from the pieces at each position and their
+    /// orientations, failing if they are not permutations and orientations.
+    pub fn try_from_parts(
+        edge_perm: [usize; 12],
+        edge_orient: [u8; 12],
+        corner_perm: [usize; 8],
+        corner_orient: [u8; 8],
+    ) -> Result<Self> {
+        Ok(Self {
+            edges: Edges::try_from_parts(edge_perm, edge_orient)?,
+            corners: Corners::try_from_parts(corner_perm, corner_orient)?,
+        })
+    }
+
+    /// Checks the invariants of the states reachable by operations: the
+    /// permutations of edges and corners have the same parity and the
+    /// orientations sum to zero.
+    pub fn check_solvable(&self) -> Result<()> {
+        if self.is_edge_permutation_even() != self.is_corner_permutation_even() {
+            return Err(anyhow!("edge and corner permutations differ in parity"));
+        }
+        if !self.edges.is_orientation_solvable() {
+            return Err(anyhow!("an edge is flipped"));
+        }
+        if !self.corners.is_orientation_solvable() {
+            return Err(anyhow!("a corner is twisted"));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the edge piece placed at `position` and its orientation.
+    pub fn edge(&self, position: usize) -> (usize, u8) {
+        self.edges.edge(position)
+    }
+
+    /// Returns the corner piece placed at `position` and its orientation.
+    pub fn corner(&self, position: usize) -> (usize, u8) {
+        self.corners.corner(position)
+    }
+
+    pub fn edges(&self) -> &Edges {
+        &self.edges
+    }
+
+    pub fn corners(&self) -> &Corners {
+        &self.corners
+    }
+
+    pub fn from_pieces(edges: Edges, corners: Corners) -> Self {
+        Self { edges, corners }
+    }
+
+    /// Returns the rotation with the corners and solved edges.
+    pub fn from_corners(corners: Corners) -> Self {
+        Self {
+            corners,
+            ..Self::default()
         }
     }
 
+    pub fn is_edge_permutation_even(&self) -> bool {
+        self.edges.is_permutation_even()
+    }
+
+    pub fn is_corner_permutation_even(&self) -> bool {
+        self.corners.is_permutation_even()
+    }
+
     pub fn edge_hamming_distance(&self, other: &Self) -> usize {
-        (0..12usize)
-            .filter(|i| self.edge_perm[*i] != other.edge_perm[*i])
-            .filter(|i| self.edge_orient[*i] != other.edge_orient[*i])
-            .count()
+        self.edges.hamming_distance(&other.edges)
     }
 
     pub fn corner_hamming_distance(&self, other: &Self) -> usize {
-        (0..8usize)
-            .filter(|i| self.corner_perm[*i] != other.corner_perm[*i])
-            .filter(|i| self.corner_orient[*i] != other.corner_orient[*i])
-            .count()
+        self.corners.hamming_distance(&other.corners)
+    }
+
+    /// Returns the permutation of the 48 stickers of the edges and corners:
+    /// sticker `t` of position `j` is `2 * j + t` for the edges and
+    /// `24 + 3 * j + t` for the corners, counting `t` from the reference
+    /// sticker in the direction of the orientations. The permutation holds
+    /// the sticker of the solved cube at each sticker, and the products of
+    /// rotations map to the products of their permutations.
+    pub fn stickers(&self) -> SymmetricGroup<48> {
+        let mut value = [0; 48];
+        for j in 0..12 {
+            let (piece, orient) = self.edge(j);
+            for t in 0..2 {
+                value[2 * j + t] = 2 * piece + (t + 2 - orient as usize) % 2;
+            }
+        }
+        for j in 0..8 {
+            let (piece, orient) = self.corner(j);
+            for t in 0..3 {
+                value[24 + 3 * j + t] = 24 + 3 * piece + (t + 3 - orient as usize) % 3;
+            }
+        }
+
+        value.try_into().unwrap()
     }
 }
 
@@ -45,29 +132,17 @@ impl Mul for &Rotation {
     type Output = Rotation;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        let perm_edge_orient = rhs.edge_perm.permute(&self.edge_orient.into()).into();
-        let perm_corner_orient = rhs.corner_perm.permute(&self.corner_orient.into()).into();
-
-        Self::Output::new(
-            &self.edge_perm * &rhs.edge_perm,
-            &perm_edge_orient + &rhs.edge_orient,
-            &self.corner_perm * &rhs.corner_perm,
-            &perm_corner_orient + &rhs.corner_orient,
-        )
+        Self::Output {
+            edges: &self.edges * &rhs.edges,
+            corners: &self.corners * &rhs.corners,
+        }
     }
 }
 
 impl MulAssign<&Rotation> for Rotation {
     fn mul_assign(&mut self, rhs: &Rotation) {
-        self.edge_perm *= &rhs.edge_perm;
-
-        self.edge_orient = rhs.edge_perm.permute(&self.edge_orient.into()).into();
-        self.edge_orient += rhs.edge_orient;
-
-        self.corner_perm *= &rhs.corner_perm;
-
-        self.corner_orient = rhs.corner_perm.permute(&self.corner_orient.into()).into();
-        self.corner_orient += rhs.corner_orient;
+        self.edges *= &rhs.edges;
+        self.corners *= &rhs.corners;
     }
 }
 
@@ -114,7 +189,7 @@ pub mod rotations {
     pub static F: LazyLock<Rotation> = LazyLock::new(|| {
         Rotation::new(
             [0, 1, 6, 10, 4, 5, 3, 7, 8, 9, 2, 11].try_into().unwrap(),
-            [0, 0, 1, 1, 1, 0, 0, 0, 1, 0, 0, 0].into(),
+            [0, 0, 1, 1, 0, 0, 1, 0, 0, 0, 1, 0].into(),
             [0, 1, 3, 7, 4, 5, 2, 6].try_into().unwrap(),
             [0, 0, 1, 2, 0, 0, 2, 1].into(),
         )
@@ -162,6 +237,15 @@ mod tests {
         assert_eq!(r1, r2);
     }
 
+    #[test]
+    fn test_stickers() {
+        let a = &*rotations::F * &*rotations::R;
+        let b = &*rotations::U * &*rotations::B3;
+
+        assert_eq!((&a * &b).stickers(), &a.stickers() * &b.stickers());
+        assert_eq!(Rotation::default().stickers(), SymmetricGroup::identity());
+    }
+
     #[test]
     fn test_r() {
         let mut rot = Rotation::default();
@@ -257,4 +341,17 @@ mod tests {
     fn test_b_b3() {
         assert_eq!(&*rotations::B * &*rotations::B3, Rotation::default());
     }
+
+    #[test]
+    fn test_order_of_fu() {
+        let fu = &*rotations::F * &*rotations::U;
+        let mut rot = fu.clone();
+        let mut order = 1;
+        while rot != Rotation::default() {
+            rot *= &fu;
+            order += 1;
+        }
+
+        assert_eq!(order, 105);
+    }
 }
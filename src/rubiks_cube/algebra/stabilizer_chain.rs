@@ -0,0 +1,445 @@
+use rand::prelude::*;
+
+use super::symmetric_group::SymmetricGroup;
+
+/// A level of a stabilizer chain: the orbit of its base point under the
+/// strong generators fixing all earlier base points.
+#[derive(Debug, Clone)]
+struct Level<const N: usize> {
+    base: usize,
+    generators: Vec<SymmetricGroup<N>>,
+    /// For each point of the orbit, a permutation mapping the base point to
+    /// it, along with its inverse
+    transversal: Vec<Option<(SymmetricGroup<N>, SymmetricGroup<N>)>>,
+}
+
+impl<const N: usize> Level<N> {
+    fn new(base: usize, generators: Vec<SymmetricGroup<N>>) -> Self {
+        let mut level = Self {
+            base,
+            generators,
+            transversal: vec![],
+        };
+        level.update_orbit();
+        level
+    }
+
+    /// Recomputes the orbit and transversal after adding generators.
+    fn update_orbit(&mut self) {
+        let identity = SymmetricGroup::identity();
+        self.transversal = vec![None; N];
+        self.transversal[self.base] = Some((identity.clone(), identity));
+        let mut orbit = vec![self.base];
+        let mut i = 0;
+        while i < orbit.len() {
+            let (u, _) = self.transversal[orbit[i]].clone().unwrap();
+            for s in &self.generators {
+                let image = s[orbit[i]];
+                if self.transversal[image].is_none() {
+                    let next = s * &u;
+                    let inverse = next.inverse();
+                    self.transversal[image] = Some((next, inverse));
+                    orbit.push(image);
+                }
+            }
+            i += 1;
+        }
+    }
+
+    fn orbit(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..N).filter(|point| self.transversal[*point].is_some())
+    }
+}
+
+/// Stabilizer chain of a permutation group computed by the Schreier–Sims
+/// algorithm, giving the order of the group and membership testing.
+///
+/// Permutations act on points as functions, `g` mapping `i` to `g[i]`, so
+/// that the product `a * b` applies `b` first.
+#[derive(Debug, Clone)]
+pub struct StabilizerChain<const N: usize> {
+    levels: Vec<Level<N>>,
+}
+
+impl<const N: usize> StabilizerChain<N> {
+    /// Computes the chain of the group generated by `generators`.
+    pub fn new(generators: &[SymmetricGroup<N>]) -> Self {
+        Self::with_base_prefix(generators, &[])
+    }
+
+    /// Computes the chain of the group generated by `generators` whose base
+    /// starts with `prefix`.
+    pub fn with_base_prefix(generators: &[SymmetricGroup<N>], prefix: &[usize]) -> Self {
+        let identity = SymmetricGroup::identity();
+        let generators = generators
+            .iter()
+            .filter(|g| **g != identity)
+            .cloned()
+            .collect::<Vec<_>>();
+        // extend the base until no generator fixes all base points
+        let mut bases = prefix.to_vec();
+        for g in &generators {
+            if bases.iter().all(|b| g[*b] == *b) {
+                bases.push((0..N).find(|i| g[*i] != *i).unwrap());
+            }
+        }
+        let levels = bases
+            .iter()
+            .enumerate()
+            .map(|(k, base)| {
+                let fixing = generators
+                    .iter()
+                    .filter(|g| bases[..k].iter().all(|b| g[*b] == *b))
+                    .cloned()
+                    .collect();
+                Level::new(*base, fixing)
+            })
+            .collect();
+        let mut chain = Self { levels };
+
+        // check the Schreier generators of each level from the bottom,
+        // going back down whenever one is missing from the levels below
+        let mut i = chain.levels.len();
+        while i > 0 {
+            match chain.missing_schreier_generator(i - 1) {
+                Some((residue, level)) => {
+                    if level == chain.levels.len() {
+                        let base = (0..N).find(|p| residue[*p] != *p).unwrap();
+                        chain.levels.push(Level::new(base, vec![]));
+                    }
+                    for l in i..=level {
+                        chain.levels[l].generators.push(residue.clone());
+                        chain.levels[l].update_orbit();
+                    }
+                    i = level + 1;
+                }
+                None => i -= 1,
+            }
+        }
+
+        chain
+    }
+
+    /// Returns the residue of a Schreier generator of level `i` which does
+    /// not sift through the levels below, with the level where it stopped.
+    fn missing_schreier_generator(&self, i: usize) -> Option<(SymmetricGroup<N>, usize)> {
+        let level = &self.levels[i];
+        for point in level.orbit() {
+            let (u, _) = level.transversal[point].as_ref().unwrap();
+            for s in &level.generators {
+                let (_, v_inverse) = level.transversal[s[point]].as_ref().unwrap();
+                let schreier = &(v_inverse * s) * u;
+                let (residue, stop) = self.sift(schreier, i + 1);
+                if stop < self.levels.len() || residue != SymmetricGroup::identity() {
+                    return Some((residue, stop));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Divides `g` by the transversals from level `from` on, returning the
+    /// residue and the level whose orbit misses the image of its base point,
+    /// or the number of levels if it went through all levels.
+    fn sift(&self, mut g: SymmetricGroup<N>, from: usize) -> (SymmetricGroup<N>, usize) {
+        for (i, level) in self.levels.iter().enumerate().skip(from) {
+            match &level.transversal[g[level.base]] {
+                Some((_, inverse)) => g = inverse * &g,
+                None => return (g, i),
+            }
+        }
+
+        (g, self.levels.len())
+    }
+
+    /// Returns the base points of the chain.
+    pub fn base(&self) -> Vec<usize> {
+        self.levels.iter().map(|level| level.base).collect()
+    }
+
+    /// Returns the number of elements of the group: the product of the sizes
+    /// of the orbits.
+    pub fn order(&self) -> u128 {
+        self.levels
+            .iter()
+            .map(|level| level.orbit().count() as u128)
+            .product()
+    }
+
+    pub fn contains(&self, g: &SymmetricGroup<N>) -> bool {
+        let (residue, _) = self.sift(g.clone(), 0);
+        residue == SymmetricGroup::identity()
+    }
+
+    /// Returns true if an element of the group maps the first `length` base
+    /// points as `g` does.
+    pub fn contains_on_base(&self, g: &SymmetricGroup<N>, length: usize) -> bool {
+        let mut g = g.clone();
+        for level in &self.levels[..length] {
+            match &level.transversal[g[level.base]] {
+                Some((_, inverse)) => g = inverse * &g,
+                None => return false,
+            }
+        }
+
+        true
+    }
+}
+
+/// Word over letters, the indices of permutations, whose product is the
+/// product of the letters from the first.
+pub type Word = Vec<usize>;
+
+/// Transversals of a stabilizer chain written as short words over letters,
+/// found by the method of Minkwitz: random products of letters are sifted
+/// through the chain, keeping the shortest word reaching each point.
+#[derive(Debug, Clone)]
+pub struct Factorization<const N: usize> {
+    bases: Vec<usize>,
+    letters: Vec<SymmetricGroup<N>>,
+    /// Letters of the inverse of each letter
+    inverses: Vec<usize>,
+    /// Letter equal to the product of two letters, with `Some(None)` if
+    /// they cancel
+    merges: Vec<Vec<Option<Option<usize>>>>,
+    table: Vec<Vec<Option<(SymmetricGroup<N>, Word)>>>,
+}
+
+/// Words longer than this are not sifted further while filling the table.
+const MAX_WORD_LENGTH: usize = 1000;
+
+/// Number of random products sifted after the table is full, to shorten
+/// its words.
+const IMPROVEMENT_ROUNDS: usize = 2000;
+
+/// Number of random products sifted to fill the table before giving up,
+/// far more than the groups of the cube need.
+const MAX_FILL_ROUNDS: usize = 50_000;
+
+impl<const N: usize> Factorization<N> {
+    /// Builds the words of the transversals of `chain` over the letters,
+    /// which must be closed under inversion.
+    ///
+    /// Returns `None` if the table is not filled within [`MAX_FILL_ROUNDS`]
+    /// random products, as when the letters do not generate the group.
+    pub fn new(chain: &StabilizerChain<N>, letters: &[SymmetricGroup<N>]) -> Option<Self> {
+        let identity = SymmetricGroup::identity();
+        let inverses = letters
+            .iter()
+            .map(|a| {
+                let inverse = a.inverse();
+                letters
+                    .iter()
+                    .position(|b| *b == inverse)
+                    .expect("letters are closed under inversion")
+            })
+            .collect();
+        let merges = letters
+            .iter()
+            .map(|a| {
+                letters
+                    .iter()
+                    .map(|b| {
+                        let product = a * b;
+                        if product == identity {
+                            Some(None)
+                        } else {
+                            letters.iter().position(|c| *c == product).map(Some)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let table = chain
+            .levels
+            .iter()
+            .map(|level| {
+                let mut row = vec![None; N];
+                row[level.base] = Some((identity.clone(), vec![]));
+                row
+            })
+            .collect();
+
+        let mut factorization = Self {
+            bases: chain.base(),
+            letters: letters.to_vec(),
+            inverses,
+            merges,
+            table,
+        };
+
+        let missing = |f: &Self| {
+            chain
+                .levels
+                .iter()
+                .zip(&f.table)
+                .map(|(level, row)| level.orbit().filter(|p| row[*p].is_none()).count())
+                .sum::<usize>()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut random_word = || {
+            let length = rng.random_range(1..=24);
+            (0..length)
+                .map(|_| rng.random_range(0..letters.len()))
+                .collect::<Vec<_>>()
+        };
+        let mut rounds = 0;
+        while missing(&factorization) > 0 {
+            if rounds == MAX_FILL_ROUNDS {
+                return None;
+            }
+            factorization.insert(random_word());
+            rounds += 1;
+        }
+        for _ in 0..IMPROVEMENT_ROUNDS {
+            factorization.insert(random_word());
+        }
+
+        Some(factorization)
+    }
+
+    /// Sifts the product of the word through the table, storing it where a
+    /// point is missing or its word is shorter than the stored one.
+    fn insert(&mut self, word: Word) {
+        let mut word = self.simplify(word);
+        let mut g = self.product(&word);
+        for i in 0..self.bases.len() {
+            if word.len() > MAX_WORD_LENGTH {
+                return;
+            }
+            let point = g[self.bases[i]];
+            match &mut self.table[i][point] {
+                None => {
+                    self.table[i][point] = Some((g, word));
+                    return;
+                }
+                Some((stored, stored_word)) => {
+                    if word.len() < stored_word.len() {
+                        std::mem::swap(stored, &mut g);
+                        std::mem::swap(stored_word, &mut word);
+                    }
+                }
+            }
+
+            let (stored, stored_word) = self.table[i][point].as_ref().unwrap();
+            g = &stored.inverse() * &g;
+            let mut next = self.inverse(stored_word);
+            next.extend(word);
+            word = self.simplify(next);
+            if g == SymmetricGroup::identity() {
+                return;
+            }
+        }
+    }
+
+    /// Returns a word whose product is `g`, or `None` if `g` is not in the
+    /// group.
+    pub fn factorize(&self, g: &SymmetricGroup<N>) -> Option<Word> {
+        let mut g = g.clone();
+        let mut word = vec![];
+        for (base, row) in self.bases.iter().zip(&self.table) {
+            let (stored, stored_word) = row[g[*base]].as_ref()?;
+            word.extend(stored_word);
+            g = &stored.inverse() * &g;
+        }
+
+        (g == SymmetricGroup::identity()).then(|| self.simplify(word))
+    }
+
+    fn product(&self, word: &[usize]) -> SymmetricGroup<N> {
+        word.iter().fold(SymmetricGroup::identity(), |acc, letter| {
+            &acc * &self.letters[*letter]
+        })
+    }
+
+    fn inverse(&self, word: &[usize]) -> Word {
+        word.iter()
+            .rev()
+            .map(|letter| self.inverses[*letter])
+            .collect()
+    }
+
+    /// Merges and cancels adjacent letters.
+    fn simplify(&self, word: Word) -> Word {
+        let mut result: Word = vec![];
+        for letter in word {
+            let merge = result.last().and_then(|last| self.merges[*last][letter]);
+            match merge {
+                Some(Some(merged)) => *result.last_mut().unwrap() = merged,
+                Some(None) => {
+                    result.pop();
+                }
+                None => result.push(letter),
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order() -> anyhow::Result<()> {
+        let cycle: SymmetricGroup<5> = [1, 2, 3, 4, 0].try_into()?;
+        let swap: SymmetricGroup<5> = [1, 0, 2, 3, 4].try_into()?;
+        let three: SymmetricGroup<5> = [1, 2, 0, 3, 4].try_into()?;
+
+        assert_eq!(
+            StabilizerChain::new(&[cycle.clone(), swap.clone()]).order(),
+            120
+        );
+        assert_eq!(
+            StabilizerChain::new(std::slice::from_ref(&cycle)).order(),
+            5
+        );
+        assert_eq!(StabilizerChain::new(&[cycle.clone(), three]).order(), 60);
+        assert_eq!(StabilizerChain::<5>::new(&[]).order(), 1);
+
+        let chain = StabilizerChain::new(std::slice::from_ref(&cycle));
+        assert!(chain.contains(&(&cycle * &cycle)));
+        assert!(!chain.contains(&swap));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_contains_on_base() -> anyhow::Result<()> {
+        let cycle: SymmetricGroup<5> = [1, 2, 3, 4, 0].try_into()?;
+        let swap: SymmetricGroup<5> = [1, 0, 2, 3, 4].try_into()?;
+        let chain = StabilizerChain::with_base_prefix(std::slice::from_ref(&cycle), &[2, 0]);
+
+        assert_eq!(chain.base()[..2], [2, 0]);
+        assert_eq!(chain.order(), 5);
+        // only the identity fixes 2, and it does not map 0 to 1
+        assert!(chain.contains_on_base(&swap, 1));
+        assert!(!chain.contains_on_base(&swap, 2));
+        assert!(chain.contains_on_base(&(&cycle * &cycle), 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_factorize() -> anyhow::Result<()> {
+        let cycle: SymmetricGroup<6> = [1, 2, 3, 4, 5, 0].try_into()?;
+        let swap: SymmetricGroup<6> = [1, 0, 2, 3, 4, 5].try_into()?;
+        let letters = [cycle.clone(), cycle.inverse(), swap.clone()];
+        let chain = StabilizerChain::new(&letters);
+        let factorization = Factorization::new(&chain, &letters).unwrap();
+
+        let g: SymmetricGroup<6> = [3, 5, 0, 1, 4, 2].try_into()?;
+        let word = factorization.factorize(&g).unwrap();
+        let product = word.iter().fold(SymmetricGroup::identity(), |acc, letter| {
+            &acc * &letters[*letter]
+        });
+
+        assert_eq!(chain.order(), 720);
+        assert_eq!(product, g);
+        // the cycles only generate the rotations of the 6 points
+        assert!(Factorization::new(&chain, &letters[..2]).is_none());
+
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use anyhow::anyhow;
 /// Represents an element of the symmetric group S_N, which is the group of all permutations
 /// of N elements. Each permutation is stored as an array where the value at index i represents
 /// where element i maps to under the permutation.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct SymmetricGroup<const N: usize> {
     /// The permutation array where value[i] = j means element i maps to position j
     value: [usize; N],
@@ -30,6 +30,36 @@ impl<const N: usize> SymmetricGroup<N> {
     {
         array::from_fn(|i| input[self.value[i]].clone())
     }
+
+    /// Returns the permutation which composes with this one to the identity
+    pub fn inverse(&self) -> Self {
+        let mut value = [0; N];
+        for (i, v) in self.value.iter().enumerate() {
+            value[*v] = i;
+        }
+        Self { value }
+    }
+
+    /// Returns true if the permutation is a product of even number of
+    /// transpositions
+    pub fn is_even(&self) -> bool {
+        let mut visited = [false; N];
+        let mut num_transpositions = 0;
+        for i in 0..N {
+            let mut j = i;
+            let mut length = 0;
+            while !visited[j] {
+                visited[j] = true;
+                j = self.value[j];
+                length += 1;
+            }
+            if length > 0 {
+                num_transpositions += length - 1;
+            }
+        }
+
+        num_transpositions % 2 == 0
+    }
 }
 
 impl<const N: usize> Default for SymmetricGroup<N> {
@@ -138,6 +168,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_inverse() -> anyhow::Result<()> {
+        let s: SymmetricGroup<4> = [2, 0, 3, 1].try_into()?;
+
+        assert_eq!(&s * &s.inverse(), SymmetricGroup::identity());
+        assert_eq!(&s.inverse() * &s, SymmetricGroup::identity());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_even() -> anyhow::Result<()> {
+        let s1: SymmetricGroup<4> = [1, 0, 2, 3].try_into()?;
+        let s2: SymmetricGroup<4> = [1, 2, 0, 3].try_into()?;
+        let s3: SymmetricGroup<4> = [1, 2, 3, 0].try_into()?;
+
+        assert!(SymmetricGroup::<4>::identity().is_even());
+        assert!(!s1.is_even());
+        assert!(s2.is_even());
+        assert!(!s3.is_even());
+
+        Ok(())
+    }
+
     #[test]
     fn test_mul_assign() -> anyhow::Result<()> {
         let s1: SymmetricGroup<3> = [2, 1, 0].try_into()?;
@@ -0,0 +1,837 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::{Mul, RangeInclusive};
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use anyhow::{Result, anyhow};
+use rand::prelude::*;
+
+use super::algebra::corners::Corners;
+use super::algebra::edges::Edges;
+use super::algebra::symmetric_group::SymmetricGroup;
+use super::facelet::{CORNERS, EDGES};
+use super::{Face, Operation};
+
+/// Turn of one or more layers parallel to a face of a big cube.
+///
+/// Layers are counted from the face, starting at 1 for the outer layer. In
+/// WCA notation `R` turns the outer layer, `Rw` the two outer layers and
+/// `3Rw` the three outer layers, and in slice notation `2R` turns the
+/// second layer alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BigMove {
+    face: Face,
+    /// The innermost layer turned, from 1.
+    depth: usize,
+    /// Whether the layers between the face and `depth` are turned as well.
+    wide: bool,
+    /// Number of clockwise quarter turns, from 1 to 3.
+    turns: u8,
+}
+
+impl BigMove {
+    /// Returns the move turning the layers of `face` up to `depth`, or only
+    /// the layer at `depth` if not `wide`, by `turns` clockwise quarter
+    /// turns. Fails unless `depth` is at least 1 and `turns` from 1 to 3.
+    pub fn new(face: Face, depth: usize, wide: bool, turns: u8) -> Result<Self> {
+        if depth == 0 {
+            return Err(anyhow!("layers are counted from 1"));
+        }
+        if !(1..=3).contains(&turns) {
+            return Err(anyhow!("invalid number of quarter turns: {turns}"));
+        }
+
+        Ok(Self {
+            face,
+            depth,
+            wide,
+            turns,
+        })
+    }
+
+    /// Returns the turn of the outer layer of the face, failing unless
+    /// `turns` is from 1 to 3.
+    pub fn face_turn(face: Face, turns: u8) -> Result<Self> {
+        Self::new(face, 1, false, turns)
+    }
+
+    pub fn face(&self) -> Face {
+        self.face
+    }
+
+    /// Returns the innermost layer turned.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns whether the layers between the face and the depth are turned
+    /// as well.
+    pub fn is_wide(&self) -> bool {
+        self.wide
+    }
+
+    /// Returns the number of clockwise quarter turns, from 1 to 3.
+    pub fn turns(&self) -> u8 {
+        self.turns
+    }
+
+    /// Returns the move which cancels this move.
+    pub fn inverse(&self) -> Self {
+        Self {
+            turns: 4 - self.turns,
+            ..*self
+        }
+    }
+
+    /// Returns the layers turned by the move.
+    pub fn layers(&self) -> RangeInclusive<usize> {
+        if self.wide {
+            1..=self.depth
+        } else {
+            self.depth..=self.depth
+        }
+    }
+
+    /// Parses moves separated by whitespace, e.g. `R 2R' 3Rw2 Uw`.
+    pub fn parse_sequence(s: &str) -> Result<Vec<BigMove>> {
+        s.split_whitespace().map(str::parse).collect()
+    }
+}
+
+impl From<Operation> for BigMove {
+    fn from(value: Operation) -> Self {
+        value.to_string().parse().unwrap()
+    }
+}
+
+impl Display for BigMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.wide, self.depth) {
+            (false, 1) | (true, 2) => {}
+            (_, depth) => write!(f, "{depth}")?,
+        }
+        write!(f, "{}", self.face)?;
+        if self.wide {
+            write!(f, "w")?;
+        }
+        match self.turns {
+            2 => write!(f, "2"),
+            3 => write!(f, "'"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for BigMove {
+    type Err = anyhow::Error;
+
+    /// Parses an optional layer number, a face letter, an optional `w` and
+    /// nothing, `2` or `'`. `3` and `2'` are accepted as well.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow!("invalid move: {s}");
+
+        let (number, rest) = s.split_at(s.chars().take_while(char::is_ascii_digit).count());
+        let mut chars = rest.chars();
+        let face = chars
+            .next()
+            .and_then(|c| Face::try_from(c).ok())
+            .ok_or_else(invalid)?;
+        let (wide, rest) = match chars.as_str().strip_prefix('w') {
+            Some(rest) => (true, rest),
+            None => (false, chars.as_str()),
+        };
+        let depth = match number {
+            "" if wide => 2,
+            "" => 1,
+            _ => number.parse().map_err(|_| invalid())?,
+        };
+        let turns = match rest {
+            "" => 1,
+            "2" | "2'" => 2,
+            "'" | "3" => 3,
+            _ => return Err(invalid()),
+        };
+
+        Self::new(face, depth, wide, turns).map_err(|_| invalid())
+    }
+}
+
+/// Cubie positions with doubled coordinates from `-(N - 1)` to `N - 1`, so
+/// that the cubies of both odd and even cubes have integer coordinates.
+type Vector = [i32; 3];
+
+fn normal(face: Face) -> Vector {
+    face.normal().map(i32::from)
+}
+
+fn dot(a: Vector, b: Vector) -> i32 {
+    (0..3).map(|k| a[k] * b[k]).sum()
+}
+
+/// Rotates the vector by quarter turns clockwise around `n` seen from
+/// outside: the component along `n` is kept and the rest goes to `v x n`.
+fn rotate(v: Vector, n: Vector, turns: u8) -> Vector {
+    (0..turns).fold(v, |v, _| {
+        let along = dot(v, n);
+        [
+            along * n[0] + v[1] * n[2] - v[2] * n[1],
+            along * n[1] + v[2] * n[0] - v[0] * n[2],
+            along * n[2] + v[0] * n[1] - v[1] * n[0],
+        ]
+    })
+}
+
+/// Returns the unit vector along the edge of the 3x3x3 cube.
+fn edge_axis(edge: usize) -> Vector {
+    let [a, b] = EDGES[edge].map(normal);
+    std::array::from_fn(|k| (a[k] == 0 && b[k] == 0) as i32)
+}
+
+/// Returns true if going from the reference face of the edge to the other
+/// face and then toward the wing at `slot` of the edge turns
+/// counterclockwise.
+///
+/// A wing keeps the edge orientation of the 3x3x3 cube when it moves to a
+/// slot of the same handedness, as its stickers are mirrored otherwise.
+fn wing_handedness(slot: usize) -> bool {
+    let edge = slot / 2;
+    let [a, b] = EDGES[edge].map(normal);
+    let axis = edge_axis(edge);
+    let cross = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+
+    (dot(cross, axis) > 0) == (slot % 2 == 1)
+}
+
+/// Positions of the pieces of each orbit of the cube of a size.
+///
+/// Corners and middle edges are in the order of [`Rotation`](super::algebra::rotation::Rotation),
+/// wings are at `2 * edge` and `2 * edge + 1` toward the negative and
+/// positive side of the edge axis, orbits of wings are ordered from the
+/// corners inward and each orbit of centers holds the positions reached
+/// from each other by rotating the whole cube.
+#[derive(Debug)]
+struct Layout {
+    size: usize,
+    corners: [Vector; 8],
+    edges: [Vector; 12],
+    middle_centers: [Vector; 6],
+    wings: Vec<[Vector; 24]>,
+    centers: Vec<[Vector; 24]>,
+    /// Faces of the positions of the centers.
+    center_faces: Vec<[Face; 24]>,
+}
+
+impl Layout {
+    fn new(size: usize) -> Self {
+        let s = size as i32 - 1;
+        let position = |faces: &[Face]| -> Vector {
+            std::array::from_fn(|k| s * faces.iter().map(|f| normal(*f)[k]).sum::<i32>())
+        };
+
+        let corners = CORNERS.map(|faces| position(&faces));
+        let edges = EDGES.map(|faces| position(&faces));
+        let middle_centers = Face::all().map(|f| position(&[f]));
+        let wings = (1..=(size.max(2) - 2) / 2)
+            .map(|k| {
+                let c = s - 2 * k as i32;
+                std::array::from_fn(|slot| {
+                    let t = if slot % 2 == 0 { -c } else { c };
+                    let axis = edge_axis(slot / 2);
+                    std::array::from_fn(|k| edges[slot / 2][k] + t * axis[k])
+                })
+            })
+            .collect();
+
+        let inner = (-s + 2..=s - 2).step_by(2).collect::<Vec<_>>();
+        let mut positions = vec![];
+        for face in Face::all() {
+            let n = normal(face);
+            let (u, v) = ([n[1], n[2], n[0]], [n[2], n[0], n[1]]);
+            for a in &inner {
+                for b in &inner {
+                    if (*a, *b) != (0, 0) {
+                        positions.push(std::array::from_fn(|k| s * n[k] + a * u[k] + b * v[k]));
+                    }
+                }
+            }
+        }
+        let mut centers: Vec<[Vector; 24]> = vec![];
+        for p in &positions {
+            if centers.iter().any(|orbit| orbit.contains(p)) {
+                continue;
+            }
+            let mut orbit = vec![*p];
+            let mut i = 0;
+            while i < orbit.len() {
+                for axis in [Face::R, Face::U] {
+                    let q = rotate(orbit[i], normal(axis), 1);
+                    if !orbit.contains(&q) {
+                        orbit.push(q);
+                    }
+                }
+                i += 1;
+            }
+            orbit.sort_by_key(|q| positions.iter().position(|p| p == q));
+            centers.push(
+                orbit
+                    .try_into()
+                    .expect("orbits of centers have 24 positions"),
+            );
+        }
+
+        let center_faces = centers
+            .iter()
+            .map(|orbit| {
+                orbit.map(|p| {
+                    Face::all()
+                        .into_iter()
+                        .find(|f| dot(p, normal(*f)) == s)
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        Self {
+            size,
+            corners,
+            edges,
+            middle_centers,
+            wings,
+            centers,
+            center_faces,
+        }
+    }
+
+    /// Returns the layer of the position counted from the face.
+    fn layer(&self, position: Vector, face: Face) -> usize {
+        ((self.size as i32 - 1 - dot(position, normal(face))) / 2 + 1) as usize
+    }
+
+    /// Returns the pieces after the move from the solved state.
+    fn pieces_of(&self, m: &BigMove) -> Pieces {
+        let n = normal(m.face);
+        let layers = m.layers();
+        let image = |v: Vector, position: Vector| {
+            if layers.contains(&self.layer(position, m.face)) {
+                rotate(v, n, m.turns)
+            } else {
+                v
+            }
+        };
+        let perm = |slots: &[Vector]| {
+            let mut perm = vec![0; slots.len()];
+            for (i, p) in slots.iter().enumerate() {
+                let q = image(*p, *p);
+                perm[slots.iter().position(|s| *s == q).unwrap()] = i;
+            }
+            perm
+        };
+        // the orientation is the index of the face of the new position
+        // where the reference sticker of the piece is
+        let orient = |slots: &[Vector], faces: &[&[Face]], perm: &[usize]| {
+            (0..slots.len())
+                .map(|j| {
+                    let i = perm[j];
+                    let reference = image(normal(faces[i][0]), slots[i]);
+                    faces[j]
+                        .iter()
+                        .position(|f| normal(*f) == reference)
+                        .unwrap() as u8
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let corner_perm = perm(&self.corners);
+        let corner_faces = CORNERS.iter().map(|f| &f[..]).collect::<Vec<_>>();
+        let corner_orient = orient(&self.corners, &corner_faces, &corner_perm);
+        let corners = Corners::try_from_parts(
+            corner_perm.try_into().unwrap(),
+            corner_orient.try_into().unwrap(),
+        )
+        .unwrap();
+
+        let edges = (self.size % 2 == 1).then(|| {
+            let edge_perm = perm(&self.edges);
+            let edge_faces = EDGES.iter().map(|f| &f[..]).collect::<Vec<_>>();
+            let edge_orient = orient(&self.edges, &edge_faces, &edge_perm);
+            Edges::try_from_parts(
+                edge_perm.try_into().unwrap(),
+                edge_orient.try_into().unwrap(),
+            )
+            .unwrap()
+        });
+        let middle_centers = (self.size % 2 == 1).then(|| group(perm(&self.middle_centers)));
+        let orbits =
+            |orbits: &[[Vector; 24]]| orbits.iter().map(|slots| group(perm(slots))).collect();
+
+        Pieces {
+            corners,
+            edges,
+            middle_centers,
+            wings: orbits(&self.wings),
+            centers: orbits(&self.centers),
+        }
+    }
+}
+
+fn group<const M: usize>(perm: Vec<usize>) -> SymmetricGroup<M> {
+    <[usize; M]>::try_from(perm).unwrap().try_into().unwrap()
+}
+
+/// Pieces of each orbit of a big cube, independent of the size so that
+/// the moves of all sizes can be cached together.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Pieces {
+    corners: Corners,
+    /// Middle edges of the odd cubes.
+    edges: Option<Edges>,
+    /// Middle centers of the odd cubes, which are moved by the middle
+    /// slices.
+    middle_centers: Option<SymmetricGroup<6>>,
+    wings: Vec<SymmetricGroup<24>>,
+    centers: Vec<SymmetricGroup<24>>,
+}
+
+impl Pieces {
+    fn solved(size: usize) -> Self {
+        let odd = size % 2 == 1 && size > 1;
+        let inner = size.max(2) - 2;
+
+        Self {
+            corners: Corners::default(),
+            edges: odd.then(Edges::default),
+            middle_centers: odd.then(SymmetricGroup::identity),
+            wings: vec![SymmetricGroup::identity(); inner / 2],
+            centers: vec![SymmetricGroup::identity(); (inner * inner - inner % 2) / 4],
+        }
+    }
+}
+
+impl Mul for &Pieces {
+    type Output = Pieces;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let zip = |a: &[SymmetricGroup<24>], b: &[SymmetricGroup<24>]| {
+            a.iter().zip(b).map(|(a, b)| a * b).collect()
+        };
+
+        Pieces {
+            corners: &self.corners * &rhs.corners,
+            edges: self
+                .edges
+                .as_ref()
+                .zip(rhs.edges.as_ref())
+                .map(|(a, b)| a * b),
+            middle_centers: self
+                .middle_centers
+                .as_ref()
+                .zip(rhs.middle_centers.as_ref())
+                .map(|(a, b)| a * b),
+            wings: zip(&self.wings, &rhs.wings),
+            centers: zip(&self.centers, &rhs.centers),
+        }
+    }
+}
+
+static LAYOUTS: LazyLock<Mutex<HashMap<usize, Arc<Layout>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Pieces after each move from the solved state by size and move.
+type MoveCache = HashMap<(usize, BigMove), Arc<Pieces>>;
+
+static MOVES: LazyLock<Mutex<MoveCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn layout(size: usize) -> Arc<Layout> {
+    let mut layouts = LAYOUTS.lock().unwrap();
+    layouts
+        .entry(size)
+        .or_insert_with(|| Arc::new(Layout::new(size)))
+        .clone()
+}
+
+fn move_pieces(size: usize, m: &BigMove) -> Arc<Pieces> {
+    // the layers beyond the cube would silently turn nothing
+    assert!(m.depth <= size, "{m} turns more than {size} layers");
+    if let Some(pieces) = MOVES.lock().unwrap().get(&(size, *m)) {
+        return pieces.clone();
+    }
+
+    let pieces = Arc::new(layout(size).pieces_of(m));
+    MOVES.lock().unwrap().insert((size, *m), pieces.clone());
+    pieces
+}
+
+/// Represents the state of an NxNxN cube by the permutations of its orbits
+/// of pieces: the corners, the middle edges and the middle centers of the
+/// odd cubes, the orbits of 24 wing edges and the orbits of 24 centers.
+///
+/// Corners and middle edges are oriented as in [`Cube`](super::Cube). Wings
+/// and centers have no orientation of their own as each of their positions
+/// holds them one way only.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigCube<const N: usize> {
+    pieces: Pieces,
+}
+
+impl<const N: usize> Mul for &BigCube<N> {
+    type Output = BigCube<N>;
+
+    /// Composes the states as [`Rotation`](super::algebra::rotation::Rotation)
+    /// does, so that the moves leading to `rhs` are played after `self`.
+    fn mul(self, rhs: Self) -> Self::Output {
+        BigCube {
+            pieces: &self.pieces * &rhs.pieces,
+        }
+    }
+}
+
+impl<const N: usize> Default for BigCube<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> BigCube<N> {
+    pub fn new() -> Self {
+        const { assert!(N >= 2, "big cubes have at least 2 layers") };
+
+        Self {
+            pieces: Pieces::solved(N),
+        }
+    }
+
+    /// Returns the moves of WCA scrambles: turns of the outer layers of a
+    /// face up to half of the cube.
+    pub fn moves() -> Vec<BigMove> {
+        let mut result = vec![];
+        for face in Face::all() {
+            for depth in 1..=N / 2 {
+                for turns in 1..=3 {
+                    result.push(BigMove {
+                        face,
+                        depth,
+                        wide: depth > 1,
+                        turns,
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns the state after `steps` random moves of [`BigCube::moves`].
+    pub fn random(steps: usize) -> (Self, Vec<BigMove>) {
+        let mut rng = rand::rng();
+        let all_moves = Self::moves();
+        let moves = (0..steps)
+            .map(|_| *all_moves.choose(&mut rng).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut cube = Self::new();
+        cube.apply_moves(&moves);
+
+        (cube, moves)
+    }
+
+    /// Parses moves as [`BigMove::parse_sequence`], failing on moves of more
+    /// layers than the cube has.
+    pub fn parse_moves(s: &str) -> Result<Vec<BigMove>> {
+        let moves = BigMove::parse_sequence(s)?;
+        if let Some(m) = moves.iter().find(|m| m.depth > N) {
+            return Err(anyhow!("{m} turns more than {N} layers"));
+        }
+
+        Ok(moves)
+    }
+
+    /// Applies the move, panicking if it turns more layers than the cube
+    /// has, which [`BigCube::parse_moves`] checks.
+    pub fn apply_move(&mut self, m: &BigMove) {
+        self.pieces = &self.pieces * &move_pieces(N, m);
+    }
+
+    /// Returns the state after the move, panicking as
+    /// [`BigCube::apply_move`].
+    pub fn move_applied(&self, m: &BigMove) -> Self {
+        Self {
+            pieces: &self.pieces * &move_pieces(N, m),
+        }
+    }
+
+    pub fn apply_moves(&mut self, moves: &[BigMove]) {
+        for m in moves {
+            self.apply_move(m);
+        }
+    }
+
+    /// Returns true if every face shows a single color. The centers of a
+    /// color look the same, so they may be permuted among themselves.
+    pub fn is_solved(&self) -> bool {
+        let solved = Pieces::solved(N);
+        self.pieces.corners == solved.corners
+            && self.pieces.edges == solved.edges
+            && self.pieces.middle_centers == solved.middle_centers
+            && self.pieces.wings == solved.wings
+            && self.are_centers_solved()
+    }
+
+    /// Returns true if each center shows the color of its face.
+    pub fn are_centers_solved(&self) -> bool {
+        let layout = layout(N);
+        layout
+            .center_faces
+            .iter()
+            .zip(&self.pieces.centers)
+            .all(|(faces, perm)| (0..24).all(|p| faces[perm[p]] == faces[p]))
+    }
+
+    pub fn corners(&self) -> &Corners {
+        &self.pieces.corners
+    }
+
+    /// Returns the middle edges, which only the odd cubes have.
+    pub fn middle_edges(&self) -> Option<&Edges> {
+        self.pieces.edges.as_ref()
+    }
+
+    pub fn num_wing_orbits(&self) -> usize {
+        self.pieces.wings.len()
+    }
+
+    pub fn num_center_orbits(&self) -> usize {
+        self.pieces.centers.len()
+    }
+
+    /// Returns the wing placed at `position` of the orbit with the edge
+    /// orientation it would have as the edge of a 3x3x3 cube. The wings at
+    /// `2 * edge` and `2 * edge + 1` belong to the edge.
+    pub fn wing(&self, orbit: usize, position: usize) -> (usize, u8) {
+        let piece = self.pieces.wings[orbit][position];
+        let orient = wing_handedness(piece) != wing_handedness(position);
+        (piece, orient as u8)
+    }
+
+    /// Returns the center placed at `position` of the orbit.
+    pub fn center(&self, orbit: usize, position: usize) -> usize {
+        self.pieces.centers[orbit][position]
+    }
+
+    /// Returns the color of the center placed at `position` of the orbit.
+    pub fn center_color(&self, orbit: usize, position: usize) -> Face {
+        self.center_face(orbit, self.center(orbit, position))
+    }
+
+    /// Returns the face of `position` of the orbit of centers, which is the
+    /// color of the center solved there.
+    pub fn center_face(&self, orbit: usize, position: usize) -> Face {
+        layout(N).center_faces[orbit][position]
+    }
+
+    pub fn is_wing_permutation_even(&self, orbit: usize) -> bool {
+        self.pieces.wings[orbit].is_even()
+    }
+
+    pub fn is_center_permutation_even(&self, orbit: usize) -> bool {
+        self.pieces.centers[orbit].is_even()
+    }
+
+    /// Returns true if the permutation of an orbit of wings is odd, which
+    /// is changed by each quarter turn of an inner slice. Such a cube shows
+    /// a single flipped edge once the edges are paired (OLL parity) and
+    /// cannot be solved by the moves of the 3x3x3 cube.
+    pub fn has_oll_parity(&self) -> bool {
+        self.pieces.wings.iter().any(|wings| !wings.is_even())
+    }
+
+    /// Returns the edges of the 3x3x3 cube formed by the middle edges and
+    /// wings, or `None` if the pieces at an edge position do not belong to
+    /// the same edge with the same orientation. The 2x2x2 cube has no edges.
+    pub fn paired_edges(&self) -> Option<Edges> {
+        if N == 2 {
+            return None;
+        }
+
+        let mut perm = [0; 12];
+        let mut orient = [0; 12];
+        for edge in 0..12 {
+            let wings = (0..self.num_wing_orbits()).flat_map(|orbit| {
+                [2 * edge, 2 * edge + 1].map(|p| {
+                    let (piece, o) = self.wing(orbit, p);
+                    (piece / 2, o)
+                })
+            });
+            let mut pieces = self.pieces.edges.iter().map(|e| e.edge(edge)).chain(wings);
+            let first = pieces.next()?;
+            if !pieces.all(|p| p == first) {
+                return None;
+            }
+            (perm[edge], orient[edge]) = first;
+        }
+
+        Edges::try_from_parts(perm, orient).ok()
+    }
+
+    /// Returns true if the paired edges and the corners differ in
+    /// permutation parity (PLL parity), which the 3x3x3 cube never does, or
+    /// `None` if the edges are not paired.
+    pub fn has_pll_parity(&self) -> Option<bool> {
+        let edges = self.paired_edges()?;
+        Some(edges.is_permutation_even() != self.pieces.corners.is_permutation_even())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cube;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let moves = BigMove::parse_sequence("R 2R' 3Rw2 Uw 1F 4B3 Dw2'")?;
+
+        assert_eq!(
+            moves,
+            vec![
+                BigMove::face_turn(Face::R, 1)?,
+                BigMove::new(Face::R, 2, false, 3)?,
+                BigMove::new(Face::R, 3, true, 2)?,
+                BigMove::new(Face::U, 2, true, 1)?,
+                BigMove::face_turn(Face::F, 1)?,
+                BigMove::new(Face::B, 4, false, 3)?,
+                BigMove::new(Face::D, 2, true, 2)?,
+            ]
+        );
+        assert_eq!(
+            moves.iter().map(|m| m.to_string()).collect::<Vec<_>>(),
+            vec!["R", "2R'", "3Rw2", "Uw", "F", "4B'", "Dw2"]
+        );
+        for s in ["X", "0R", "Rx", "R2w", "w"] {
+            assert!(s.parse::<BigMove>().is_err(), "{s}");
+        }
+        assert!(BigCube::<4>::parse_moves("5R").is_err());
+        assert!(BigMove::new(Face::R, 0, false, 1).is_err());
+        assert!(BigMove::new(Face::R, 1, false, 0).is_err());
+        assert!(BigMove::face_turn(Face::R, 4).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_orbits() {
+        assert_eq!(BigCube::<2>::new().num_wing_orbits(), 0);
+        assert_eq!(BigCube::<2>::new().num_center_orbits(), 0);
+        assert_eq!(BigCube::<3>::new().num_center_orbits(), 0);
+        assert_eq!(BigCube::<4>::new().num_wing_orbits(), 1);
+        assert_eq!(BigCube::<4>::new().num_center_orbits(), 1);
+        assert_eq!(BigCube::<5>::new().num_center_orbits(), 2);
+        assert_eq!(BigCube::<6>::new().num_wing_orbits(), 2);
+        assert_eq!(BigCube::<6>::new().num_center_orbits(), 4);
+        assert_eq!(BigCube::<7>::new().num_center_orbits(), 6);
+        assert!(BigCube::<5>::new().middle_edges().is_some());
+        assert!(BigCube::<6>::new().middle_edges().is_none());
+    }
+
+    #[test]
+    fn test_face_turns_match_cube() -> anyhow::Result<()> {
+        let sequence = "R U F' L2 D B' R2 U' F";
+        let mut cube = Cube::new();
+        for op in crate::Operation::parse_sequence(sequence)? {
+            cube.apply_operation(&op);
+        }
+
+        let mut big = BigCube::<3>::new();
+        big.apply_moves(&BigCube::<3>::parse_moves(sequence)?);
+        assert_eq!(big.corners(), cube.rotation.corners());
+        assert_eq!(big.middle_edges(), Some(cube.rotation.edges()));
+
+        let mut big = BigCube::<4>::new();
+        big.apply_moves(&BigCube::<4>::parse_moves(sequence)?);
+        assert_eq!(big.corners(), cube.rotation.corners());
+        assert_eq!(big.paired_edges().as_ref(), Some(cube.rotation.edges()));
+        assert_eq!(big.has_pll_parity(), Some(false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_moves_have_order_four() -> anyhow::Result<()> {
+        let mut moves = vec![];
+        for face in Face::all() {
+            for depth in 1..=5 {
+                moves.push(BigMove::new(face, depth, false, 1)?);
+                moves.push(BigMove::new(face, depth, true, 1)?);
+            }
+        }
+
+        for m in moves {
+            let mut cube = BigCube::<5>::new();
+            cube.apply_move(&m);
+            assert!(!cube.is_solved(), "{m}");
+            cube.apply_move(&m.inverse());
+            assert!(cube.is_solved(), "{m}");
+            for _ in 0..4 {
+                cube.apply_move(&m);
+            }
+            assert!(cube.is_solved(), "{m}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "4R turns more than 3 layers")]
+    fn test_move_beyond_layers() {
+        BigCube::<3>::new().apply_move(&"4R".parse().unwrap());
+    }
+
+    #[test]
+    fn test_wide_move_is_product_of_slices() -> anyhow::Result<()> {
+        let mut wide = BigCube::<6>::new();
+        wide.apply_moves(&BigCube::<6>::parse_moves("3Rw U 3Fw'")?);
+        let mut slices = BigCube::<6>::new();
+        slices.apply_moves(&BigCube::<6>::parse_moves("R 2R 3R U 3F' F' 2F'")?);
+
+        assert_eq!(wide, slices);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parity() -> anyhow::Result<()> {
+        let mut cube = BigCube::<4>::new();
+        cube.apply_moves(&BigCube::<4>::parse_moves("R U 2R")?);
+        assert!(cube.has_oll_parity());
+        assert!(!cube.is_wing_permutation_even(0));
+        assert!(cube.is_center_permutation_even(0));
+        assert_eq!(cube.has_pll_parity(), None);
+
+        let mut cube = BigCube::<4>::new();
+        cube.apply_moves(&BigCube::<4>::parse_moves("Rw U2 Rw'")?);
+        assert!(!cube.has_oll_parity());
+
+        let mut cube = BigCube::<4>::new();
+        cube.apply_moves(&BigCube::<4>::parse_moves("2R2 U2 2R2 Uw2 2R2 Uw2")?);
+        assert!(!cube.has_oll_parity());
+        assert_eq!(cube.has_pll_parity(), Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_center_face() {
+        let cube = BigCube::<5>::new();
+        for orbit in 0..cube.num_center_orbits() {
+            let faces = (0..24)
+                .map(|p| cube.center_face(orbit, p))
+                .collect::<Vec<_>>();
+            for face in Face::all() {
+                assert_eq!(faces.iter().filter(|f| **f == face).count(), 4);
+            }
+        }
+    }
+}
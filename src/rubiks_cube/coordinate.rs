@@ -0,0 +1,557 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::Operation;
+use super::algebra::rotation::Rotation;
+use super::symmetry::Symmetry;
+
+/// Function projecting cube states to raw coordinate values.
+///
+/// Operations must act on the values, i.e. states with the same value must
+/// still have the same value after the same operation.
+pub type RawCoordinate = fn(&Rotation) -> usize;
+
+/// Orientations of the edges packed as bits.
+pub fn edge_orientation(rotation: &Rotation) -> usize {
+    (0..12).fold(0, |acc, i| acc << 1 | rotation.edge(i).1 as usize)
+}
+
+/// Orientations of the corners packed as base-3 digits.
+pub fn corner_orientation(rotation: &Rotation) -> usize {
+    (0..8).fold(0, |acc, i| acc * 3 + rotation.corner(i).1 as usize)
+}
+
+/// Permutation of the edges packed as 4-bit digits.
+pub fn edge_permutation(rotation: &Rotation) -> usize {
+    (0..12).fold(0, |acc, i| acc << 4 | rotation.edge(i).0)
+}
+
+/// Permutation of the corners packed as 3-bit digits.
+pub fn corner_permutation(rotation: &Rotation) -> usize {
+    (0..8).fold(0, |acc, i| acc << 3 | rotation.corner(i).0)
+}
+
+/// Positions of the edges in `pieces` packed as bits.
+pub fn edge_positions(rotation: &Rotation, pieces: &[usize]) -> usize {
+    (0..12).fold(0, |acc, i| {
+        acc << 1 | pieces.contains(&rotation.edge(i).0) as usize
+    })
+}
+
+/// A coordinate whose reachable raw values are numbered densely, with the
+/// table of the values after each operation.
+pub struct Coordinate {
+    raw: RawCoordinate,
+    dense: HashMap<usize, usize>,
+    moves: Vec<usize>,
+    num_operations: usize,
+}
+
+impl Coordinate {
+    /// Enumerates the values reachable from the solved state with
+    /// `operations` by breadth-first search.
+    pub fn new(raw: RawCoordinate, operations: &[Operation]) -> Self {
+        Self::enumerate(raw, operations).0
+    }
+
+    /// Enumerates the values as [`Coordinate::new`], returning a state of
+    /// each value as well.
+    fn enumerate(raw: RawCoordinate, operations: &[Operation]) -> (Self, Vec<Rotation>) {
+        let identity = Rotation::default();
+        let mut dense = HashMap::from([(raw(&identity), 0)]);
+        let mut representatives = vec![identity];
+        let mut moves = vec![];
+
+        let mut i = 0;
+        while i < representatives.len() {
+            for op in operations {
+                let next = &representatives[i] * op.rotation();
+                let value = raw(&next);
+                let index = match dense.get(&value) {
+                    Some(index) => *index,
+                    None => {
+                        dense.insert(value, representatives.len());
+                        representatives.push(next);
+                        representatives.len() - 1
+                    }
+                };
+                moves.push(index);
+            }
+            i += 1;
+        }
+
+        let coordinate = Self {
+            raw,
+            dense,
+            moves,
+            num_operations: operations.len(),
+        };
+        (coordinate, representatives)
+    }
+
+    pub fn size(&self) -> usize {
+        self.dense.len()
+    }
+
+    /// Returns the dense value of the state, or `None` if it is not
+    /// reachable from the solved state.
+    pub fn value(&self, rotation: &Rotation) -> Option<usize> {
+        self.dense.get(&(self.raw)(rotation)).copied()
+    }
+
+    /// Returns the value after the `operation`-th operation.
+    pub fn apply(&self, value: usize, operation: usize) -> usize {
+        self.moves[value * self.num_operations + operation]
+    }
+}
+
+/// Table of the exact distances to the solved state over the product of
+/// coordinates, counting only the given operations.
+pub struct DistanceTable {
+    coordinates: Vec<Coordinate>,
+    distance: Vec<u8>,
+}
+
+impl DistanceTable {
+    pub fn new(raws: &[RawCoordinate], operations: &[Operation]) -> Self {
+        let coordinates = raws
+            .iter()
+            .map(|raw| Coordinate::new(*raw, operations))
+            .collect::<Vec<_>>();
+        let size: usize = coordinates.iter().map(|c| c.size()).product();
+
+        let mut distance = vec![u8::MAX; size];
+        distance[0] = 0;
+        let mut queue = VecDeque::from([0]);
+        let mut values = vec![0; coordinates.len()];
+        while let Some(index) = queue.pop_front() {
+            let mut rest = index;
+            for (value, c) in values.iter_mut().zip(&coordinates).rev() {
+                *value = rest % c.size();
+                rest /= c.size();
+            }
+
+            for op in 0..operations.len() {
+                let next = values
+                    .iter()
+                    .zip(&coordinates)
+                    .fold(0, |acc, (value, c)| acc * c.size() + c.apply(*value, op));
+                if distance[next] == u8::MAX {
+                    distance[next] = distance[index] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        Self {
+            coordinates,
+            distance,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn size(&self) -> usize {
+        self.distance.len()
+    }
+
+    /// Returns the distance of the state, or `None` if it is not reachable.
+    pub fn distance(&self, rotation: &Rotation) -> Option<usize> {
+        let mut index = 0;
+        for c in &self.coordinates {
+            index = index * c.size() + c.value(rotation)?;
+        }
+
+        match self.distance[index] {
+            u8::MAX => None,
+            d => Some(d as usize),
+        }
+    }
+
+    /// Encodes the table in little endian: for each coordinate its size,
+    /// number of operations, raw values in dense order and move table,
+    /// followed by the distances.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&(self.coordinates.len() as u32).to_le_bytes());
+        for c in &self.coordinates {
+            let mut raws = vec![0; c.size()];
+            for (raw, index) in &c.dense {
+                raws[*index] = *raw;
+            }
+
+            bytes.extend_from_slice(&(c.size() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(c.num_operations as u32).to_le_bytes());
+            for raw in raws {
+                bytes.extend_from_slice(&(raw as u64).to_le_bytes());
+            }
+            for index in &c.moves {
+                bytes.extend_from_slice(&(*index as u32).to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&self.distance);
+
+        bytes
+    }
+
+    /// Decodes a table encoded by [`DistanceTable::to_bytes`] with the same
+    /// raw coordinates, or returns `None` if the bytes are inconsistent.
+    pub fn from_bytes(raws: &[RawCoordinate], bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes };
+        if reader.u32()? != raws.len() {
+            return None;
+        }
+
+        let mut coordinates = vec![];
+        for raw in raws {
+            let size = reader.u32()?;
+            let num_operations = reader.u32()?;
+            let dense = (0..size)
+                .map(|i| Some((reader.u64()?, i)))
+                .collect::<Option<HashMap<_, _>>>()?;
+            // the raw values must be distinct for the map to be a bijection
+            // onto the dense values
+            if dense.len() != size {
+                return None;
+            }
+            let moves = (0..size * num_operations)
+                .map(|_| reader.u32().filter(|index| *index < size))
+                .collect::<Option<Vec<_>>>()?;
+
+            coordinates.push(Coordinate {
+                raw: *raw,
+                dense,
+                moves,
+                num_operations,
+            });
+        }
+
+        let size: usize = coordinates.iter().map(|c| c.size()).product();
+        if reader.bytes.len() != size {
+            return None;
+        }
+
+        Some(Self {
+            coordinates,
+            distance: reader.bytes.to_vec(),
+        })
+    }
+}
+
+/// Table of the exact distances to the solved state over the product of
+/// coordinates, the first of which is reduced to its classes of values
+/// equivalent by symmetry. The table has an entry for each class of the
+/// first coordinate and each value of the others, which makes it about as
+/// many times smaller than [`DistanceTable`] as the classes are fewer than
+/// the values of the first coordinate.
+///
+/// The raw coordinates must be compatible with the symmetries, i.e. states
+/// with the same values must still have the same values after conjugation,
+/// the symmetries must form a group and the operations must be closed under
+/// conjugation so that equivalent states have the same distance.
+pub struct SymmetricDistanceTable {
+    symmetries: Vec<Symmetry>,
+    coordinates: Vec<Coordinate>,
+    /// Class of each value of the first coordinate
+    class: Vec<u32>,
+    /// Symmetry taking each value of the first coordinate to the
+    /// representative of its class
+    symmetry: Vec<u8>,
+    /// Symmetries other than the identity keeping the representative of
+    /// each class, whose conjugates must be compared to pick an entry
+    stabilizer: Vec<Vec<u8>>,
+    /// Product of the sizes of the coordinates after the first
+    rest: usize,
+    distance: Vec<u8>,
+}
+
+impl SymmetricDistanceTable {
+    pub fn new(raws: &[RawCoordinate], operations: &[Operation], symmetries: &[Symmetry]) -> Self {
+        let mut table = Self::without_distances(raws, operations, symmetries);
+
+        let identity = Rotation::default();
+        let start = table
+            .index(&identity)
+            .expect("the solved state is reachable");
+        table.distance[start] = 0;
+        let mut queue = VecDeque::from([(identity, 0)]);
+        while let Some((rotation, distance)) = queue.pop_front() {
+            for op in operations {
+                let next = &rotation * op.rotation();
+                let index = table.index(&next).expect("states are reachable");
+                if table.distance[index] == u8::MAX {
+                    table.distance[index] = distance + 1;
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        table
+    }
+
+    /// Enumerates the coordinates and the classes of the first one, with
+    /// all distances unknown.
+    fn without_distances(
+        raws: &[RawCoordinate],
+        operations: &[Operation],
+        symmetries: &[Symmetry],
+    ) -> Self {
+        let (first, representatives) = Coordinate::enumerate(raws[0], operations);
+        let size = first.size();
+        let coordinates = std::iter::once(first)
+            .chain(
+                raws[1..]
+                    .iter()
+                    .map(|raw| Coordinate::new(*raw, operations)),
+            )
+            .collect::<Vec<_>>();
+        let inverses = symmetries
+            .iter()
+            .map(|s| {
+                let inverse = s.inverse();
+                symmetries
+                    .iter()
+                    .position(|t| *t == inverse)
+                    .expect("symmetries must form a group")
+            })
+            .collect::<Vec<_>>();
+
+        // the classes are numbered by their first values, which are their
+        // representatives
+        let mut class = vec![u32::MAX; size];
+        let mut symmetry = vec![0; size];
+        let mut stabilizer = vec![];
+        for value in 0..size {
+            if class[value] != u32::MAX {
+                continue;
+            }
+            let mut keeping = vec![];
+            for (i, s) in symmetries.iter().enumerate() {
+                let image = coordinates[0]
+                    .value(&s.conjugate(&representatives[value]))
+                    .expect("operations must be closed under conjugation");
+                if image == value && *s != Symmetry::identity() {
+                    keeping.push(i as u8);
+                }
+                if class[image] == u32::MAX {
+                    class[image] = stabilizer.len() as u32;
+                    symmetry[image] = inverses[i] as u8;
+                }
+            }
+            stabilizer.push(keeping);
+        }
+
+        let rest = coordinates[1..].iter().map(|c| c.size()).product::<usize>();
+        Self {
+            symmetries: symmetries.to_vec(),
+            coordinates,
+            class,
+            symmetry,
+            distance: vec![u8::MAX; stabilizer.len() * rest],
+            stabilizer,
+            rest,
+        }
+    }
+
+    /// Returns the index of the entry of the state: the class of its first
+    /// value, and the smallest values of the other coordinates over the
+    /// conjugates of the state with the representative of the class.
+    fn index(&self, rotation: &Rotation) -> Option<usize> {
+        let value = self.coordinates[0].value(rotation)?;
+        let class = self.class[value] as usize;
+        let conjugate = self.symmetries[self.symmetry[value] as usize].conjugate(rotation);
+        let mut rest = self.rest_index(&conjugate)?;
+        for s in &self.stabilizer[class] {
+            let other = self.symmetries[*s as usize].conjugate(&conjugate);
+            rest = rest.min(self.rest_index(&other)?);
+        }
+
+        Some(class * self.rest + rest)
+    }
+
+    fn rest_index(&self, rotation: &Rotation) -> Option<usize> {
+        let mut index = 0;
+        for c in &self.coordinates[1..] {
+            index = index * c.size() + c.value(rotation)?;
+        }
+        Some(index)
+    }
+
+    /// Returns the number of entries.
+    #[cfg(test)]
+    pub fn size(&self) -> usize {
+        self.distance.len()
+    }
+
+    /// Returns the distance of the state, or `None` if it is not reachable.
+    pub fn distance(&self, rotation: &Rotation) -> Option<usize> {
+        match self.distance[self.index(rotation)?] {
+            u8::MAX => None,
+            d => Some(d as usize),
+        }
+    }
+
+    /// Encodes the table in little endian: the number of coordinates, of
+    /// classes and of values of the other coordinates, followed by the
+    /// distances. The classes are enumerated again when decoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&(self.coordinates.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.stabilizer.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.rest as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.distance);
+
+        bytes
+    }
+
+    /// Decodes a table encoded by [`SymmetricDistanceTable::to_bytes`] with
+    /// the same raw coordinates, operations and symmetries, or returns
+    /// `None` if the bytes are inconsistent.
+    pub fn from_bytes(
+        raws: &[RawCoordinate],
+        operations: &[Operation],
+        symmetries: &[Symmetry],
+        bytes: &[u8],
+    ) -> Option<Self> {
+        let mut reader = Reader { bytes };
+        if reader.u32()? != raws.len() {
+            return None;
+        }
+        let mut table = Self::without_distances(raws, operations, symmetries);
+        if reader.u64()? != table.stabilizer.len()
+            || reader.u64()? != table.rest
+            || reader.bytes.len() != table.distance.len()
+        {
+            return None;
+        }
+
+        table.distance = reader.bytes.to_vec();
+        Some(table)
+    }
+}
+
+/// Reads little endian integers from the front of bytes.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, tail) = self.bytes.split_first_chunk::<N>()?;
+        self.bytes = tail;
+        Some(*head)
+    }
+
+    fn u32(&mut self) -> Option<usize> {
+        Some(u32::from_le_bytes(self.take()?) as usize)
+    }
+
+    fn u64(&mut self) -> Option<usize> {
+        Some(u64::from_le_bytes(self.take()?) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coordinate_size() {
+        assert_eq!(
+            Coordinate::new(edge_orientation, &Operation::all()).size(),
+            2048
+        );
+        assert_eq!(
+            Coordinate::new(corner_orientation, &Operation::all()).size(),
+            2187
+        );
+    }
+
+    #[test]
+    fn test_distance_table() {
+        let table = DistanceTable::new(&[edge_orientation], &Operation::all());
+        let mut rotation = Rotation::default();
+
+        assert_eq!(table.distance(&rotation), Some(0));
+        rotation *= Operation::F.rotation();
+        assert_eq!(table.distance(&rotation), Some(1));
+        rotation *= Operation::B.rotation();
+        assert_eq!(table.distance(&rotation), Some(2));
+    }
+
+    #[test]
+    fn test_symmetric_distance_table() {
+        let operations = Operation::all();
+        let table = DistanceTable::new(&[corner_orientation], &operations);
+        let reduced = SymmetricDistanceTable::new(
+            &[corner_orientation],
+            &operations,
+            &Symmetry::preserving_ud(),
+        );
+
+        assert!(reduced.size() * 8 < table.size());
+
+        let mut rotation = Rotation::default();
+        for op in [
+            Operation::R,
+            Operation::F3,
+            Operation::U,
+            Operation::L2,
+            Operation::B,
+        ] {
+            rotation *= op.rotation();
+            assert_eq!(reduced.distance(&rotation), table.distance(&rotation));
+        }
+    }
+
+    #[test]
+    fn test_symmetric_distance_table_product() {
+        use Operation::*;
+
+        let operations = [R2, L2, U2, D2, F2, B2];
+        let raws: [RawCoordinate; 2] = [edge_permutation, corner_permutation];
+        let table = DistanceTable::new(&raws, &operations);
+        let reduced = SymmetricDistanceTable::new(&raws, &operations, &Symmetry::all());
+
+        assert_eq!(table.size(), 663552);
+        // 232 classes of edge permutations times 96 corner permutations
+        assert_eq!(reduced.size(), 22272);
+
+        let mut rotation = Rotation::default();
+        for i in 0..200 {
+            rotation *= operations[(i * 7 + i / 6) % 6].rotation();
+            assert_eq!(reduced.distance(&rotation), table.distance(&rotation));
+        }
+        let bytes = reduced.to_bytes();
+        let decoded =
+            SymmetricDistanceTable::from_bytes(&raws, &operations, &Symmetry::all(), &bytes)
+                .unwrap();
+        assert_eq!(decoded.distance(&rotation), table.distance(&rotation));
+        assert!(SymmetricDistanceTable::from_bytes(&raws, &operations, &[], &bytes).is_none());
+    }
+
+    #[test]
+    fn test_bytes() {
+        let table = DistanceTable::new(
+            &[edge_orientation, corner_orientation],
+            &[Operation::F, Operation::R],
+        );
+        let bytes = table.to_bytes();
+        let decoded = DistanceTable::from_bytes(&[edge_orientation, corner_orientation], &bytes);
+
+        assert_eq!(decoded.map(|t| t.to_bytes()), Some(bytes.clone()));
+        assert!(DistanceTable::from_bytes(&[edge_orientation], &bytes).is_none());
+        assert!(
+            DistanceTable::from_bytes(
+                &[edge_orientation, corner_orientation],
+                &bytes[..bytes.len() - 1]
+            )
+            .is_none()
+        );
+
+        // the second raw value of the first coordinate written over the first
+        let mut repeated = bytes.clone();
+        repeated.copy_within(20..28, 12);
+        assert!(
+            DistanceTable::from_bytes(&[edge_orientation, corner_orientation], &repeated).is_none()
+        );
+    }
+}
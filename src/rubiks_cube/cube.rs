@@ -29,7 +29,7 @@ use super::algebra::rotation::Rotation;
 ///   and 1 if they are different.
 ///
 /// Corners (0 to 7):
-///   UBR, UFR, UFL, UBL, DBR, DFB, DFL, DBL
+///   UBL, UBR, UFR, UFL, DBL, DBR, DFR, DFL
 ///
 ///   The corner of edge parts is represented as permutation
 ///   from their initial positions.
@@ -39,7 +39,7 @@ use super::algebra::rotation::Rotation;
 ///   orientation of the piece that was originally there. This number
 ///   corresponds to clockwise rotations of 0, 1, or 2 when viewed from
 ///   the outside of the cube.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Cube {
     pub rotation: Rotation,
 }
@@ -75,4 +75,32 @@ impl Cube {
             rotation: &self.rotation * operation.rotation(),
         }
     }
+
+    /// Returns the order of the state: the number of times the operations
+    /// reaching it must be repeated to come back to the solved cube.
+    pub fn order(&self) -> usize {
+        let identity = Rotation::default();
+        let mut rotation = self.rotation.clone();
+        let mut order = 1;
+        while rotation != identity {
+            rotation *= &self.rotation;
+            order += 1;
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order() {
+        let mut cube = Cube::new();
+        assert_eq!(cube.order(), 1);
+
+        cube.apply_operation(&Operation::R);
+        cube.apply_operation(&Operation::U);
+        assert_eq!(cube.order(), 105);
+    }
 }
@@ -0,0 +1,166 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use rand::prelude::*;
+
+/// States of a part of a puzzle reachable from its solved state, numbered
+/// in breadth-first order from 0 for the solved state, with the state
+/// reached by each move from each state.
+///
+/// Enumerating the reachable states gives dense indices for the parts of
+/// small puzzles, whose coordinates would leave most indices unreachable.
+pub(super) struct Enumeration<T> {
+    states: Vec<T>,
+    indices: HashMap<T, u32>,
+    num_moves: usize,
+    /// Index of the state reached by move `m` from state `i` at
+    /// `i * num_moves + m`
+    transitions: Vec<u32>,
+}
+
+impl<T: Clone + Eq + Hash> Enumeration<T> {
+    /// Enumerates the states reachable from `solved` by `num_moves` moves,
+    /// `apply(state, m)` giving the state after move `m`.
+    pub fn new(solved: T, num_moves: usize, apply: impl Fn(&T, usize) -> T) -> Self {
+        let mut states = vec![solved.clone()];
+        let mut indices = HashMap::from([(solved, 0)]);
+        let mut transitions = vec![];
+        let mut i = 0;
+        while i < states.len() {
+            for m in 0..num_moves {
+                let next = apply(&states[i], m);
+                let index = *indices.entry(next.clone()).or_insert_with(|| {
+                    states.push(next);
+                    states.len() as u32 - 1
+                });
+                transitions.push(index);
+            }
+            i += 1;
+        }
+
+        Self {
+            states,
+            indices,
+            num_moves,
+            transitions,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns a uniformly random state.
+    pub fn random(&self) -> &T {
+        &self.states[rand::rng().random_range(0..self.len())]
+    }
+
+    /// Returns the index of the state, or `None` if it is not reachable.
+    pub fn index(&self, state: &T) -> Option<usize> {
+        self.indices.get(state).map(|index| *index as usize)
+    }
+
+    pub fn next(&self, index: usize, m: usize) -> usize {
+        self.transitions[index * self.num_moves + m] as usize
+    }
+}
+
+/// Returns the distances to the solved state of the pairs of states of `a`
+/// and `b` moved by the same moves, at `i * b.len() + j`, and `u8::MAX` for
+/// the pairs which are not reachable.
+pub(super) fn distances<A, B>(a: &Enumeration<A>, b: &Enumeration<B>) -> Vec<u8>
+where
+    A: Clone + Eq + Hash,
+    B: Clone + Eq + Hash,
+{
+    assert_eq!(a.num_moves, b.num_moves);
+
+    let mut table = vec![u8::MAX; a.len() * b.len()];
+    let mut queue = VecDeque::from([(0, 0)]);
+    table[0] = 0;
+    while let Some((i, j)) = queue.pop_front() {
+        let distance = table[i * b.len() + j];
+        for m in 0..a.num_moves {
+            let (next_i, next_j) = (a.next(i, m), b.next(j, m));
+            let entry = &mut table[next_i * b.len() + next_j];
+            if *entry == u8::MAX {
+                *entry = distance + 1;
+                queue.push_back((next_i, next_j));
+            }
+        }
+    }
+
+    table
+}
+
+/// Returns the shortest moves solving `state`, following moves which
+/// decrease `distance`, as given by a table of all the states.
+pub(super) fn descend<S: Clone, M: Copy>(
+    state: &S,
+    moves: &[M],
+    distance: impl Fn(&S) -> usize,
+    apply: impl Fn(&S, &M) -> S,
+) -> Vec<M> {
+    let mut result = vec![];
+    let mut state = state.clone();
+    let mut remaining = distance(&state);
+    while remaining > 0 {
+        let (m, next) = moves
+            .iter()
+            .map(|m| (*m, apply(&state, m)))
+            .find(|(_, next)| distance(next) < remaining)
+            .expect("a move decreases the distance");
+        result.push(m);
+        state = next;
+        remaining -= 1;
+    }
+
+    result
+}
+
+/// Returns the moves reaching a state from the solved state given the
+/// moves solving it: their inverses in reverse order.
+pub(super) fn scramble<M>(solution: &[M], inverse: impl Fn(&M) -> M) -> Vec<M> {
+    solution.iter().rev().map(inverse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Distances of the hours of a clock to 0 turning by 1 or 5 hours.
+    const DISTANCES: [usize; 12] = [0, 1, 2, 3, 2, 1, 2, 1, 2, 3, 2, 1];
+
+    /// Turns the hour hand of a clock by 1 or 5 hours either way.
+    fn turn(hour: &i32, m: &usize) -> i32 {
+        (hour + [1, 11, 5, 7][*m]) % 12
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let clock = Enumeration::new(0, 4, |hour, m| turn(hour, &m));
+
+        assert_eq!(clock.len(), 12);
+        assert_eq!(clock.index(&12), None);
+        assert_eq!(clock.index(&5), Some(clock.next(0, 2)));
+        assert!(clock.index(clock.random()).is_some());
+
+        let table = distances(&clock, &Enumeration::new((), 4, |_, _| ()));
+        for hour in 0..12 {
+            let distance = table[clock.index(&hour).unwrap()] as usize;
+            assert_eq!(distance, DISTANCES[hour as usize]);
+        }
+    }
+
+    #[test]
+    fn test_descend() {
+        let distance = |hour: &i32| DISTANCES[*hour as usize];
+
+        let solution = descend(&6, &[0, 1, 2, 3], distance, turn);
+        assert_eq!(solution.len(), 2);
+        assert_eq!(solution.iter().fold(6, |hour, m| turn(&hour, m)), 0);
+
+        let moves = scramble(&solution, |m| m ^ 1);
+        assert_eq!(moves.iter().fold(0, |hour, m| turn(&hour, m)), 6);
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+
+use super::algebra::rotation::Rotation;
+
+/// Faces of the cube, also used as the colors of the stickers which are
+/// on the face in the solved state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Face {
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+}
+
+impl Face {
+    pub fn all() -> [Face; 6] {
+        [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B]
+    }
+
+    /// Returns the outward normal vector of the face, with x toward R,
+    /// y toward U and z toward F.
+    pub fn normal(&self) -> [i8; 3] {
+        match self {
+            Face::U => [0, 1, 0],
+            Face::R => [1, 0, 0],
+            Face::F => [0, 0, 1],
+            Face::D => [0, -1, 0],
+            Face::L => [-1, 0, 0],
+            Face::B => [0, 0, -1],
+        }
+    }
+
+    /// Returns the face with the normal vector.
+    pub fn from_normal(normal: [i8; 3]) -> Option<Face> {
+        Face::all().into_iter().find(|f| f.normal() == normal)
+    }
+
+    /// Returns the directions of the columns and the rows of the stickers
+    /// when the face is seen in the unfolded net.
+    fn axes(&self) -> ([i8; 3], [i8; 3]) {
+        match self {
+            Face::U => ([1, 0, 0], [0, 0, 1]),
+            Face::R => ([0, 0, -1], [0, -1, 0]),
+            Face::F => ([1, 0, 0], [0, -1, 0]),
+            Face::D => ([1, 0, 0], [0, 0, -1]),
+            Face::L => ([0, 0, 1], [0, -1, 0]),
+            Face::B => ([-1, 0, 0], [0, -1, 0]),
+        }
+    }
+}
+
+impl Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl TryFrom<char> for Face {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'U' => Ok(Face::U),
+            'R' => Ok(Face::R),
+            'F' => Ok(Face::F),
+            'D' => Ok(Face::D),
+            'L' => Ok(Face::L),
+            'B' => Ok(Face::B),
+            _ => Err(anyhow!("invalid face: {value}")),
+        }
+    }
+}
+
+use Face::*;
+
+/// Faces of the edge positions. The first face is the reference for edge
+/// orientation: U or D if the edge has one, otherwise F or B.
+pub(super) const EDGES: [[Face; 2]; 12] = [
+    [B, L],
+    [B, R],
+    [F, R],
+    [F, L],
+    [U, B],
+    [U, R],
+    [U, F],
+    [U, L],
+    [D, B],
+    [D, R],
+    [D, F],
+    [D, L],
+];
+
+/// Faces of the corner positions, starting from U or D and going clockwise
+/// seen from outside the corner. The orientation of a corner is the index
+/// of the face where its U or D sticker is.
+pub(super) const CORNERS: [[Face; 3]; 8] = [
+    [U, L, B],
+    [U, B, R],
+    [U, R, F],
+    [U, F, L],
+    [D, B, L],
+    [D, R, B],
+    [D, F, R],
+    [D, L, F],
+];
+
+/// A sticker identified by the position of its cubie and its normal.
+pub type Sticker = ([i8; 3], [i8; 3]);
+
+/// Stickers in the facelet order: faces U, R, F, D, L and B, each of which
+/// in reading order as seen in the unfolded net.
+pub static STICKERS: LazyLock<[Sticker; 54]> = LazyLock::new(|| {
+    std::array::from_fn(|i| {
+        let face = Face::all()[i / 9];
+        let (row, col) = ((i % 9 / 3) as i8 - 1, (i % 3) as i8 - 1);
+        let (right, down) = face.axes();
+        let normal = face.normal();
+        let position = std::array::from_fn(|k| normal[k] + col * right[k] + row * down[k]);
+        (position, normal)
+    })
+});
+
+/// Returns the index of the sticker in the facelet order.
+pub fn sticker_index(sticker: &Sticker) -> usize {
+    STICKERS.iter().position(|s| s == sticker).unwrap()
+}
+
+/// Returns the index of the sticker of the cubie between `faces` on `face`.
+fn index_on(faces: &[Face], face: Face) -> usize {
+    let position = std::array::from_fn(|k| faces.iter().map(|f| f.normal()[k]).sum());
+    sticker_index(&(position, face.normal()))
+}
+
+/// Indices of the stickers of the edge positions in the order of [`EDGES`].
+pub static EDGE_STICKERS: LazyLock<[[usize; 2]; 12]> =
+    LazyLock::new(|| EDGES.map(|faces| faces.map(|f| index_on(&faces, f))));
+
+/// Indices of the stickers of the corner positions in the order of
+/// [`CORNERS`].
+pub static CORNER_STICKERS: LazyLock<[[usize; 3]; 8]> =
+    LazyLock::new(|| CORNERS.map(|faces| faces.map(|f| index_on(&faces, f))));
+
+/// Colors of the 54 stickers of the cube in the facelet order.
+///
+/// As a string, the colors are written as the faces where they belong,
+/// e.g. `UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB` for the
+/// solved cube.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Facelets {
+    colors: [Face; 54],
+}
+
+impl Facelets {
+    pub fn new(colors: [Face; 54]) -> Self {
+        Self { colors }
+    }
+
+    pub fn colors(&self) -> &[Face; 54] {
+        &self.colors
+    }
+
+    /// Returns the colors of the stickers of a face in reading order.
+    pub fn face(&self, face: Face) -> &[Face] {
+        let i = Face::all().iter().position(|f| *f == face).unwrap();
+        &self.colors[i * 9..i * 9 + 9]
+    }
+
+    pub fn from_rotation(rotation: &Rotation) -> Self {
+        let mut colors: [Face; 54] = std::array::from_fn(|i| Face::all()[i / 9]);
+
+        for (position, stickers) in EDGE_STICKERS.iter().enumerate() {
+            let (piece, orient) = rotation.edge(position);
+            for k in 0..2 {
+                colors[stickers[(k + orient as usize) % 2]] = EDGES[piece][k];
+            }
+        }
+        for (position, stickers) in CORNER_STICKERS.iter().enumerate() {
+            let (piece, orient) = rotation.corner(position);
+            for k in 0..3 {
+                colors[stickers[(k + orient as usize) % 3]] = CORNERS[piece][k];
+            }
+        }
+
+        Self { colors }
+    }
+
+    /// Converts the stickers to the cubies, failing if the stickers do not
+    /// form the pieces of a cube or the pieces cannot be reached by
+    /// operations.
+    pub fn to_rotation(&self) -> Result<Rotation> {
+        if let Some(face) = Face::all()
+            .into_iter()
+            .find(|face| self.colors[index_on(&[*face], *face)] != *face)
+        {
+            return Err(anyhow!("center of {face} is not {face}"));
+        }
+
+        let mut edge_perm = [0; 12];
+        let mut edge_orient = [0; 12];
+        for (position, (faces, stickers)) in EDGES.iter().zip(&*EDGE_STICKERS).enumerate() {
+            let colors = stickers.map(|i| self.colors[i]);
+            let (piece, orient) = (0..12)
+                .flat_map(|piece| (0..2).map(move |o| (piece, o)))
+                .find(|(piece, o)| (0..2).all(|k| colors[(k + o) % 2] == EDGES[*piece][k]))
+                .ok_or_else(|| anyhow!("invalid edge {colors:?} at {faces:?}"))?;
+            edge_perm[position] = piece;
+            edge_orient[position] = orient as u8;
+        }
+
+        let mut corner_perm = [0; 8];
+        let mut corner_orient = [0; 8];
+        for (position, (faces, stickers)) in CORNERS.iter().zip(&*CORNER_STICKERS).enumerate() {
+            let colors = stickers.map(|i| self.colors[i]);
+            let (piece, orient) = (0..8)
+                .flat_map(|piece| (0..3).map(move |o| (piece, o)))
+                .find(|(piece, o)| (0..3).all(|k| colors[(k + o) % 3] == CORNERS[*piece][k]))
+                .ok_or_else(|| anyhow!("invalid corner {colors:?} at {faces:?}"))?;
+            corner_perm[position] = piece;
+            corner_orient[position] = orient as u8;
+        }
+
+        let rotation =
+            Rotation::try_from_parts(edge_perm, edge_orient, corner_perm, corner_orient)?;
+        rotation.check_solvable()?;
+
+        Ok(rotation)
+    }
+}
+
+impl Default for Facelets {
+    fn default() -> Self {
+        Self::from_rotation(&Rotation::default())
+    }
+}
+
+impl Display for Facelets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for color in &self.colors {
+            write!(f, "{color}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Facelets {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let colors = s
+            .trim()
+            .chars()
+            .map(Face::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        let colors: [Face; 54] = colors
+            .try_into()
+            .map_err(|c: Vec<_>| anyhow!("expected 54 facelets, found {}", c.len()))?;
+
+        Ok(Self { colors })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubiks_cube::Operation;
+
+    /// Rotates the stickers on the face a quarter turn clockwise.
+    fn turn(facelets: &Facelets, face: Face) -> Facelets {
+        let n = face.normal();
+        let mut colors = facelets.colors;
+        for (i, (position, normal)) in STICKERS.iter().enumerate() {
+            if (0..3).map(|k| position[k] * n[k]).sum::<i8>() != 1 {
+                continue;
+            }
+            // clockwise rotation around n seen from outside: the component
+            // along n is kept and the rest goes to v x n
+            let rotate = |v: [i8; 3]| {
+                let along = (0..3).map(|k| v[k] * n[k]).sum::<i8>();
+                [
+                    along * n[0] + v[1] * n[2] - v[2] * n[1],
+                    along * n[1] + v[2] * n[0] - v[0] * n[2],
+                    along * n[2] + v[0] * n[1] - v[1] * n[0],
+                ]
+            };
+            colors[sticker_index(&(rotate(*position), rotate(*normal)))] = facelets.colors[i];
+        }
+        Facelets { colors }
+    }
+
+    #[test]
+    fn test_solved() {
+        assert_eq!(
+            Facelets::default().to_string(),
+            "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"
+        );
+    }
+
+    #[test]
+    fn test_from_rotation() {
+        for (op, face) in [
+            (Operation::R, R),
+            (Operation::L, L),
+            (Operation::U, U),
+            (Operation::D, D),
+            (Operation::F, F),
+            (Operation::B, B),
+        ] {
+            assert_eq!(
+                Facelets::from_rotation(op.rotation()),
+                turn(&Facelets::default(), face),
+                "{op}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_rotation() -> anyhow::Result<()> {
+        let mut rotation = Rotation::default();
+        for op in [Operation::R, Operation::U3, Operation::F, Operation::B2] {
+            rotation *= op.rotation();
+        }
+
+        assert_eq!(Facelets::from_rotation(&rotation).to_rotation()?, rotation);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> anyhow::Result<()> {
+        let facelets = Facelets::from_rotation(Operation::R.rotation());
+
+        assert_eq!(facelets.to_string().parse::<Facelets>()?, facelets);
+        assert!("UUU".parse::<Facelets>().is_err());
+        assert!(
+            "XUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"
+                .parse::<Facelets>()
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_rotation_invalid() -> anyhow::Result<()> {
+        let mut colors = *Facelets::default().colors();
+        colors.swap(8, 9);
+        assert!(Facelets::new(colors).to_rotation().is_err());
+
+        let twisted = "UUUUUUUURRRRRRRRRFFFFFFFFFUDDDDDDDDDLLLLLLLLLBBBBBBBBB".parse::<Facelets>();
+        assert!(twisted.is_err() || twisted?.to_rotation().is_err());
+
+        Ok(())
+    }
+}
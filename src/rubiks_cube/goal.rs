@@ -0,0 +1,184 @@
+use super::algebra::rotation::Rotation;
+
+/// Represents a (partial) goal of solving as masks over the pieces.
+///
+/// Each mask is indexed by piece, using the same numbering as [`super::Cube`].
+/// A piece whose permutation flag is set must be placed at its initial
+/// position, and a piece whose orientation flag is set must have orientation
+/// 0 wherever it is placed. Pieces with neither flag set are don't-care.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Goal {
+    edge_perm: [bool; 12],
+    edge_orient: [bool; 12],
+    corner_perm: [bool; 8],
+    corner_orient: [bool; 8],
+}
+
+impl Goal {
+    pub fn new(
+        edge_perm: [bool; 12],
+        edge_orient: [bool; 12],
+        corner_perm: [bool; 8],
+        corner_orient: [bool; 8],
+    ) -> Self {
+        Self {
+            edge_perm,
+            edge_orient,
+            corner_perm,
+            corner_orient,
+        }
+    }
+
+    /// Returns the goal where every piece is solved.
+    pub fn solved() -> Self {
+        Self::new([true; 12], [true; 12], [true; 8], [true; 8])
+    }
+
+    /// Returns the goal where every piece is don't-care.
+    pub fn none() -> Self {
+        Self::new([false; 12], [false; 12], [false; 8], [false; 8])
+    }
+
+    /// Returns the goal with the D cross (DB, DR, DF, DL) solved.
+    pub fn cross() -> Self {
+        Self::none().with_edges(&[8, 9, 10, 11])
+    }
+
+    /// Returns the goal with the D face layer solved.
+    pub fn first_layer() -> Self {
+        Self::cross().with_corners(&[4, 5, 6, 7])
+    }
+
+    /// Returns the goal with the D face layer and the middle layer solved.
+    pub fn first_two_layers() -> Self {
+        Self::first_layer().with_edges(&[0, 1, 2, 3])
+    }
+
+    /// Returns the goal with all edges oriented regardless of their positions.
+    pub fn edge_orientation() -> Self {
+        Self::new([false; 12], [true; 12], [false; 8], [false; 8])
+    }
+
+    /// Returns the goal with all corners oriented regardless of their
+    /// positions.
+    pub fn corner_orientation() -> Self {
+        Self::new([false; 12], [false; 12], [false; 8], [true; 8])
+    }
+
+    /// Adds the given edge pieces to the goal (both permutation and
+    /// orientation).
+    pub fn with_edges(mut self, pieces: &[usize]) -> Self {
+        for &piece in pieces {
+            self.edge_perm[piece] = true;
+            self.edge_orient[piece] = true;
+        }
+        self
+    }
+
+    /// Adds the given corner pieces to the goal (both permutation and
+    /// orientation).
+    pub fn with_corners(mut self, pieces: &[usize]) -> Self {
+        for &piece in pieces {
+            self.corner_perm[piece] = true;
+            self.corner_orient[piece] = true;
+        }
+        self
+    }
+
+    /// Merges two goals so that the pieces required by either are required.
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            std::array::from_fn(|i| self.edge_perm[i] || other.edge_perm[i]),
+            std::array::from_fn(|i| self.edge_orient[i] || other.edge_orient[i]),
+            std::array::from_fn(|i| self.corner_perm[i] || other.corner_perm[i]),
+            std::array::from_fn(|i| self.corner_orient[i] || other.corner_orient[i]),
+        )
+    }
+
+    pub fn edge_perm(&self, piece: usize) -> bool {
+        self.edge_perm[piece]
+    }
+
+    pub fn edge_orient(&self, piece: usize) -> bool {
+        self.edge_orient[piece]
+    }
+
+    pub fn corner_perm(&self, piece: usize) -> bool {
+        self.corner_perm[piece]
+    }
+
+    pub fn corner_orient(&self, piece: usize) -> bool {
+        self.corner_orient[piece]
+    }
+
+    /// Returns the number of edge pieces which do not satisfy the goal.
+    pub fn unsolved_edges(&self, rotation: &Rotation) -> usize {
+        (0..12usize)
+            .filter(|&position| {
+                let (piece, orient) = rotation.edge(position);
+                (self.edge_perm[piece] && piece != position)
+                    || (self.edge_orient[piece] && orient != 0)
+            })
+            .count()
+    }
+
+    /// Returns the number of corner pieces which do not satisfy the goal.
+    pub fn unsolved_corners(&self, rotation: &Rotation) -> usize {
+        (0..8usize)
+            .filter(|&position| {
+                let (piece, orient) = rotation.corner(position);
+                (self.corner_perm[piece] && piece != position)
+                    || (self.corner_orient[piece] && orient != 0)
+            })
+            .count()
+    }
+
+    pub fn is_satisfied(&self, rotation: &Rotation) -> bool {
+        self.unsolved_edges(rotation) == 0 && self.unsolved_corners(rotation) == 0
+    }
+}
+
+impl Default for Goal {
+    fn default() -> Self {
+        Self::solved()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubiks_cube::algebra::rotation::rotations;
+
+    #[test]
+    fn test_solved() {
+        let goal = Goal::solved();
+
+        assert!(goal.is_satisfied(&Rotation::default()));
+        assert!(!goal.is_satisfied(&rotations::R));
+    }
+
+    #[test]
+    fn test_none() {
+        assert!(Goal::none().is_satisfied(&rotations::F));
+    }
+
+    #[test]
+    fn test_cross() {
+        let goal = Goal::cross();
+
+        assert!(goal.is_satisfied(&rotations::U));
+        assert!(!goal.is_satisfied(&rotations::R));
+        assert_eq!(goal.unsolved_edges(&rotations::R), 1);
+        assert_eq!(goal.unsolved_corners(&rotations::R), 0);
+    }
+
+    #[test]
+    fn test_edge_orientation() {
+        let goal = Goal::edge_orientation();
+
+        assert!(goal.is_satisfied(&rotations::R));
+        assert!(goal.is_satisfied(&rotations::F2));
+        assert!(!goal.is_satisfied(&rotations::F));
+        assert_eq!(goal.unsolved_edges(&rotations::F), 4);
+    }
+}
@@ -1,9 +1,44 @@
 mod algebra;
+mod big_cube;
+mod coordinate;
 mod cube;
+mod enumeration;
+mod facelet;
+mod goal;
 mod operation;
-mod search;
+mod pocket_cube;
+pub mod puzzle;
+mod pyraminx;
+mod reduction;
+mod render;
+pub mod search;
+#[cfg(feature = "serde")]
+mod serialization;
+mod skewb;
 mod solve;
+mod subgroup;
+mod svg;
+mod symmetry;
+mod table_cache;
+mod thistlethwaite;
 
+pub use big_cube::{BigCube, BigMove};
 pub use cube::Cube;
+pub use facelet::{Face, Facelets};
+pub use goal::Goal;
 pub use operation::Operation;
-pub use solve::solve;
+pub use pocket_cube::{PocketCube, PocketMove};
+pub use pyraminx::{Pyraminx, PyraminxAxis, PyraminxMove};
+pub use reduction::{ReductionSolution, ReductionStep, solve_reduction};
+pub use render::{ColorScheme, Net};
+pub use skewb::{Skewb, SkewbAxis, SkewbMove};
+pub use solve::{
+    Cancellation, SearchStats, Solver, Strategy, solve, solve_goal, solve_in_subgroup,
+};
+pub use subgroup::{Subgroup, SubgroupChain};
+pub use svg::Svg;
+pub use symmetry::Symmetry;
+pub use table_cache::{TABLE_DIR_ENV, set_table_directory, table_directory};
+pub use thistlethwaite::{
+    PhaseSolution, ThistlethwaiteSolution, solve_thistlethwaite, solve_thistlethwaite_with_stats,
+};
@@ -1,8 +1,13 @@
 use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
 
 use super::algebra::rotation::{Rotation, rotations};
+use super::search::MoveAutomaton;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Operation {
     R,
     R2,
@@ -48,6 +53,50 @@ impl Operation {
         }
     }
 
+    /// Returns the number of quarter turns of the operation, its length in
+    /// the quarter turn metric.
+    pub fn quarter_turns(&self) -> usize {
+        match self.index() % 3 {
+            1 => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns the operation which cancels this operation.
+    pub fn inverse(&self) -> Operation {
+        use Operation::*;
+        match self {
+            R => R3,
+            R3 => R,
+            L => L3,
+            L3 => L,
+            U => U3,
+            U3 => U,
+            D => D3,
+            D3 => D,
+            F => F3,
+            F3 => F,
+            B => B3,
+            B3 => B,
+            R2 | L2 | U2 | D2 | F2 | B2 => *self,
+        }
+    }
+
+    /// Returns the operations generated by repeating this operation,
+    /// e.g. `[R, R2, R']` for `R` and `[R2]` for `R2`.
+    pub fn powers(&self) -> Vec<Operation> {
+        use Operation::*;
+        match self {
+            R | R3 => Operation::right(),
+            L | L3 => Operation::left(),
+            U | U3 => Operation::up(),
+            D | D3 => Operation::down(),
+            F | F3 => Operation::front(),
+            B | B3 => Operation::back(),
+            R2 | L2 | U2 | D2 | F2 | B2 => vec![*self],
+        }
+    }
+
     pub fn all() -> Vec<Operation> {
         vec![
             Operation::R,
@@ -71,6 +120,36 @@ impl Operation {
         ]
     }
 
+    /// Parses operations in standard notation separated by whitespace, e.g.
+    /// `R U' F2`.
+    pub fn parse_sequence(s: &str) -> Result<Vec<Operation>> {
+        s.split_whitespace().map(str::parse).collect()
+    }
+
+    /// Returns the index of the operation in [`Operation::all`].
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Returns the automaton of canonical sequences of operations, derived
+    /// from which operations commute and which merge into one, with the
+    /// operations numbered by [`Operation::index`].
+    pub fn automaton() -> &'static MoveAutomaton {
+        &AUTOMATON
+    }
+
+    /// Returns the operations which may follow a canonical sequence ending
+    /// in `state` of [`Operation::automaton`], with the states after them.
+    /// Operations on the same face never follow each other, and of the
+    /// operations on opposite faces only R before L, U before D and F
+    /// before B are kept.
+    pub fn canonical_next(state: usize) -> impl Iterator<Item = (Operation, usize)> {
+        let all = Operation::all();
+        AUTOMATON
+            .moves(state)
+            .map(move |(index, next)| (all[index], next))
+    }
+
     pub fn right() -> Vec<Operation> {
         vec![Operation::R, Operation::R2, Operation::R3]
     }
@@ -96,6 +175,19 @@ impl Operation {
     }
 }
 
+static AUTOMATON: LazyLock<MoveAutomaton> = LazyLock::new(|| {
+    let all = Operation::all();
+    let rotation = |i: usize| all[i].rotation();
+    MoveAutomaton::new(
+        all.len(),
+        |a, b| rotation(a) * rotation(b) == rotation(b) * rotation(a),
+        |a, b| {
+            let product = rotation(a) * rotation(b);
+            product == Rotation::default() || all.iter().any(|op| *op.rotation() == product)
+        },
+    )
+});
+
 impl Display for Operation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -121,3 +213,90 @@ impl Display for Operation {
         write!(f, "{s}")
     }
 }
+
+impl FromStr for Operation {
+    type Err = anyhow::Error;
+
+    /// Parses a face letter followed by nothing, `2` or `'`. `3` and `2'`
+    /// are accepted as well.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let face = match chars.next() {
+            Some('R') => Operation::right(),
+            Some('L') => Operation::left(),
+            Some('U') => Operation::up(),
+            Some('D') => Operation::down(),
+            Some('F') => Operation::front(),
+            Some('B') => Operation::back(),
+            _ => return Err(anyhow!("invalid operation: {s}")),
+        };
+        match chars.as_str() {
+            "" => Ok(face[0]),
+            "2" | "2'" => Ok(face[1]),
+            "'" | "3" => Ok(face[2]),
+            _ => Err(anyhow!("invalid operation: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sequence() -> anyhow::Result<()> {
+        use Operation::*;
+
+        assert_eq!(
+            Operation::parse_sequence(" R U' F2  B3 D2'\n")?,
+            vec![R, U3, F2, B3, D2]
+        );
+        for op in Operation::all() {
+            assert_eq!(op.to_string().parse::<Operation>()?, op);
+        }
+        assert!(Operation::parse_sequence("R X").is_err());
+        assert!(Operation::parse_sequence("R2x").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_sequence_counts() {
+        let mut states = vec![MoveAutomaton::START];
+        let mut counts = vec![];
+        for _ in 0..4 {
+            states = states
+                .iter()
+                .flat_map(|s| Operation::canonical_next(*s).map(|(_, next)| next))
+                .collect();
+            counts.push(states.len());
+        }
+
+        assert_eq!(counts, vec![18, 243, 3240, 43254]);
+    }
+
+    #[test]
+    fn test_canonical_next() {
+        use Operation::*;
+        let automaton = Operation::automaton();
+        let accepts = |ops: &[Operation]| {
+            automaton.accepts(&ops.iter().map(|op| op.index()).collect::<Vec<_>>())
+        };
+
+        assert!(accepts(&[R, L, U, D2]));
+        assert!(!accepts(&[R, R2]));
+        assert!(!accepts(&[L, R]));
+        assert!(!accepts(&[R, L3, R]));
+        assert_eq!(Operation::canonical_next(MoveAutomaton::START).count(), 18);
+    }
+
+    #[test]
+    fn test_quarter_turns() -> anyhow::Result<()> {
+        let operations = Operation::parse_sequence("R U2 F' B2 D")?;
+        let qtm: usize = operations.iter().map(|op| op.quarter_turns()).sum();
+
+        assert_eq!(qtm, 7);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+use rand::prelude::*;
+
+use super::algebra::corners::Corners;
+use super::algebra::rotation::Rotation;
+use super::coordinate::{self, DistanceTable, RawCoordinate};
+use super::{Operation, enumeration, table_cache};
+
+/// Moves of the 2x2x2 cube: turns of R, U and F.
+///
+/// Turning L, D or B is the same as turning the opposite face and the whole
+/// cube, so these moves reach every state while keeping the DBL corner
+/// fixed, which makes the states of the cube its 3,674,160 corner states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PocketMove {
+    R,
+    R2,
+    R3,
+    U,
+    U2,
+    U3,
+    F,
+    F2,
+    F3,
+}
+
+impl PocketMove {
+    pub fn all() -> Vec<PocketMove> {
+        use PocketMove::*;
+        vec![R, R2, R3, U, U2, U3, F, F2, F3]
+    }
+
+    /// Returns the operation of the 3x3x3 cube which turns the same face.
+    pub fn operation(&self) -> Operation {
+        match self {
+            PocketMove::R => Operation::R,
+            PocketMove::R2 => Operation::R2,
+            PocketMove::R3 => Operation::R3,
+            PocketMove::U => Operation::U,
+            PocketMove::U2 => Operation::U2,
+            PocketMove::U3 => Operation::U3,
+            PocketMove::F => Operation::F,
+            PocketMove::F2 => Operation::F2,
+            PocketMove::F3 => Operation::F3,
+        }
+    }
+
+    fn corners(&self) -> &'static Corners {
+        self.operation().rotation().corners()
+    }
+
+    /// Returns the move which cancels this move.
+    pub fn inverse(&self) -> PocketMove {
+        PocketMove::try_from(self.operation().inverse()).unwrap()
+    }
+
+    /// Parses moves in standard notation separated by whitespace, e.g.
+    /// `R U' F2`.
+    pub fn parse_sequence(s: &str) -> Result<Vec<PocketMove>> {
+        s.split_whitespace().map(str::parse).collect()
+    }
+}
+
+impl TryFrom<Operation> for PocketMove {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Operation) -> Result<Self, Self::Error> {
+        PocketMove::all()
+            .into_iter()
+            .find(|m| m.operation() == value)
+            .ok_or_else(|| anyhow!("{value} is not a move of the 2x2x2 cube"))
+    }
+}
+
+impl FromStr for PocketMove {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PocketMove::try_from(s.parse::<Operation>()?)
+    }
+}
+
+impl Display for PocketMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.operation())
+    }
+}
+
+/// Coordinates of the corners identifying all states of the 2x2x2 cube.
+const RAWS: [RawCoordinate; 2] = [
+    coordinate::corner_permutation,
+    coordinate::corner_orientation,
+];
+
+/// Distances of all states of the 2x2x2 cube to the solved state.
+static TABLE: LazyLock<DistanceTable> = LazyLock::new(|| {
+    let operations = PocketMove::all()
+        .iter()
+        .map(|m| m.operation())
+        .collect::<Vec<_>>();
+    // bump the version whenever the coordinates change
+    table_cache::load_or_generate(
+        "pocket-cube",
+        table_cache::fnv1a(b"pocket-cube v1 R U F"),
+        || DistanceTable::new(&RAWS, &operations),
+        DistanceTable::to_bytes,
+        |bytes| DistanceTable::from_bytes(&RAWS, bytes),
+    )
+});
+
+/// Represents 2x2x2 cube state by corner permutation and corner orientation
+/// as in [`Cube`](super::Cube), with the DBL corner (4) fixed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PocketCube {
+    corners: Corners,
+}
+
+impl PocketCube {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a uniformly random state.
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+
+        let mut perm = [0, 1, 2, 3, 5, 6, 7];
+        perm.shuffle(&mut rng);
+        let mut orient = [0u8; 7];
+        for o in orient.iter_mut().skip(1) {
+            *o = rng.random_range(0..3);
+        }
+        orient[0] = (3 - orient.iter().sum::<u8>() % 3) % 3;
+
+        let corners = Corners::try_from_parts(
+            [
+                perm[0], perm[1], perm[2], perm[3], 4, perm[4], perm[5], perm[6],
+            ],
+            [
+                orient[0], orient[1], orient[2], orient[3], 0, orient[4], orient[5], orient[6],
+            ],
+        )
+        .unwrap();
+
+        Self { corners }
+    }
+
+    /// Returns a uniformly random state and the shortest moves turning the
+    /// solved cube into it.
+    pub fn scramble() -> (Self, Vec<PocketMove>) {
+        let cube = Self::random();
+        let moves = enumeration::scramble(&cube.solve(), PocketMove::inverse);
+
+        (cube, moves)
+    }
+
+    /// Returns the corner piece at `position` and its orientation.
+    pub fn corner(&self, position: usize) -> (usize, u8) {
+        self.corners.corner(position)
+    }
+
+    pub fn apply_move(&mut self, m: &PocketMove) {
+        self.corners *= m.corners();
+    }
+
+    pub fn move_applied(&self, m: &PocketMove) -> Self {
+        Self {
+            corners: &self.corners * m.corners(),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.corners == Corners::default()
+    }
+
+    /// Returns the number of moves of the shortest solution.
+    pub fn distance(&self) -> usize {
+        TABLE
+            .distance(&Rotation::from_corners(self.corners.clone()))
+            .expect("all corner states with DBL fixed are reachable")
+    }
+
+    /// Returns the shortest moves solving the cube. The table of all its
+    /// states is generated on first use.
+    pub fn solve(&self) -> Vec<PocketMove> {
+        enumeration::descend(self, &PocketMove::all(), Self::distance, Self::move_applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sequence() -> anyhow::Result<()> {
+        use PocketMove::*;
+
+        assert_eq!(PocketMove::parse_sequence("R U' F2")?, vec![R, U3, F2]);
+        assert!(PocketMove::parse_sequence("R L").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_size() {
+        assert_eq!(TABLE.size(), 3674160);
+    }
+
+    #[test]
+    fn test_solve() -> anyhow::Result<()> {
+        let mut cube = PocketCube::new();
+        for m in PocketMove::parse_sequence("R U F' R2 U'")? {
+            cube.apply_move(&m);
+        }
+
+        let solution = cube.solve();
+        for m in &solution {
+            cube.apply_move(m);
+        }
+
+        assert_eq!(solution.len(), 5);
+        assert!(cube.is_solved());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scramble() {
+        let (cube, moves) = PocketCube::scramble();
+        let mut scrambled = PocketCube::new();
+        for m in &moves {
+            scrambled.apply_move(m);
+        }
+
+        assert_eq!(scrambled, cube);
+        assert!(moves.len() <= 11);
+        assert_eq!(cube.corner(4), (4, 0));
+    }
+}
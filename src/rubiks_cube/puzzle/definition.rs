@@ -0,0 +1,175 @@
+use anyhow::{Result, anyhow};
+
+/// Value assigned to a key in a definition file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Value {
+    String(String),
+    Integer(i64),
+    Array(Vec<i64>),
+}
+
+/// Assignment of a value to a key, including the keys of its table header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Entry {
+    pub line: usize,
+    pub key: Vec<String>,
+    pub value: Value,
+}
+
+/// Parses the subset of TOML used by definition files: `[table]` headers,
+/// dotted keys, comments, and values which are strings, integers or arrays
+/// of integers, the latter possibly spanning several lines.
+pub(super) fn parse(text: &str) -> Result<Vec<Entry>> {
+    let mut entries = vec![];
+    let mut table = vec![];
+    let mut lines = text.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let number = index + 1;
+        let mut line = strip_comment(line).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| anyhow!("line {number}: unterminated table header"))?;
+            table = parse_key(header).map_err(|e| anyhow!("line {number}: {e}"))?;
+            continue;
+        }
+
+        while line.contains('[') && !line.contains(']') {
+            let (_, next) = lines
+                .next()
+                .ok_or_else(|| anyhow!("line {number}: unterminated array"))?;
+            line.push(' ');
+            line.push_str(strip_comment(next).trim());
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("line {number}: expected `key = value`"))?;
+        let mut key_path = table.clone();
+        key_path.extend(parse_key(key).map_err(|e| anyhow!("line {number}: {e}"))?);
+        let value = parse_value(value.trim()).map_err(|e| anyhow!("line {number}: {e}"))?;
+
+        entries.push(Entry {
+            line: number,
+            key: key_path,
+            value,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Removes a comment starting with `#` outside of a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parses a dotted key of bare words such as `moves.R.corners`.
+fn parse_key(s: &str) -> Result<Vec<String>> {
+    s.split('.')
+        .map(|part| {
+            let part = part.trim();
+            let valid = !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if valid {
+                Ok(part.to_string())
+            } else {
+                Err(anyhow!("invalid key {s:?}"))
+            }
+        })
+        .collect()
+}
+
+fn parse_value(s: &str) -> Result<Value> {
+    if let Some(rest) = s.strip_prefix('"') {
+        let string = rest
+            .strip_suffix('"')
+            .filter(|inner| !inner.contains(['"', '\\']))
+            .ok_or_else(|| anyhow!("invalid string {s}"))?;
+        return Ok(Value::String(string.to_string()));
+    }
+
+    if let Some(rest) = s.strip_prefix('[') {
+        let inner = rest
+            .strip_suffix(']')
+            .ok_or_else(|| anyhow!("invalid array {s}"))?;
+        let mut items = inner.split(',').map(str::trim).collect::<Vec<_>>();
+        // a trailing comma is allowed
+        if items.last() == Some(&"") {
+            items.pop();
+        }
+        let array = items
+            .iter()
+            .map(|item| parse_integer(item))
+            .collect::<Result<_>>()?;
+        return Ok(Value::Array(array));
+    }
+
+    parse_integer(s).map(Value::Integer)
+}
+
+fn parse_integer(s: &str) -> Result<i64> {
+    s.replace('_', "")
+        .parse()
+        .map_err(|_| anyhow!("invalid integer {s:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let text = r#"
+            name = "Toy" # comment
+            [orbits.pieces]
+            size = 3
+            [moves.A]
+            pieces.permutation = [
+                1, 2,
+                0,
+            ]
+        "#;
+
+        let entries = parse(text)?;
+        let keys = entries
+            .iter()
+            .map(|entry| (entry.key.join("."), entry.value.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            keys,
+            vec![
+                ("name".to_string(), Value::String("Toy".to_string())),
+                ("orbits.pieces.size".to_string(), Value::Integer(3)),
+                (
+                    "moves.A.pieces.permutation".to_string(),
+                    Value::Array(vec![1, 2, 0])
+                ),
+            ]
+        );
+        assert_eq!(entries[2].line, 6);
+
+        assert!(parse("[orbits.pieces").is_err());
+        assert!(parse("size = [1, 2").is_err());
+        assert!(parse("name = \"unterminated").is_err());
+        assert!(parse("bad key = 1").is_err());
+
+        Ok(())
+    }
+}
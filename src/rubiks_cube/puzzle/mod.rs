@@ -0,0 +1,708 @@
+//! Permutation puzzles defined by data instead of code.
+//!
+//! The states of a twisty puzzle form a product of wreath products, one per
+//! orbit of pieces: a permutation of the pieces of the orbit along with an
+//! orientation of each piece modulo the number of ways it can sit in its
+//! place. The rotations of [`Cube`](super::Cube) are the case of the 3x3x3
+//! cube with its 12 edges modulo 2 and 8 corners modulo 3.
+//!
+//! A [`Puzzle`] is read from a definition in a subset of TOML giving the
+//! orbits and the generator moves, the latter as the piece at each position
+//! and its orientation after the move, as in the definition of the 2x2x2
+//! cube:
+//!
+//! ```toml
+//! name = "2x2x2"
+//!
+//! [orbits.corners]
+//! size = 8
+//! orientation = 3
+//!
+//! [moves.R]
+//! corners.permutation = [0, 2, 6, 3, 4, 1, 5, 7]
+//! corners.orientation = [0, 1, 2, 0, 0, 2, 1, 0]
+//! ```
+//!
+//! The orientation of an orbit defaults to 1, i.e. pieces without
+//! orientation, and orbits left out of a move are unchanged by it. Every
+//! power of a generator is a move as well, written with the power after the
+//! name, or `'` for the inverse: `R`, `R2` and `R'` for a quarter turn.
+//!
+//! The states are searched by the algorithms of [`search`] through
+//! [`PuzzleNode`]. See `puzzles/` for definitions of other puzzles.
+
+mod definition;
+
+use std::fmt::Display;
+use std::ops::{Mul, MulAssign};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+use super::search::{
+    self, Invertible, Keyed, MoveAutomaton, Prunable, SearchNode, Searchable, State,
+};
+use definition::{Entry, Value};
+
+/// Most moves of a puzzle, counting the powers of the generators, as the
+/// move automaton keeps sets of moves in 64 bits.
+const MAX_MOVES: usize = 64;
+
+/// Pieces of a puzzle which can move into each other's places.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orbit {
+    pub name: String,
+    pub size: usize,
+    /// Number of orientations of each piece
+    pub orientation: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct OrbitState {
+    perm: Vec<usize>,
+    orient: Vec<u8>,
+    modulus: u8,
+}
+
+impl OrbitState {
+    fn identity(orbit: &Orbit) -> Self {
+        Self {
+            perm: (0..orbit.size).collect(),
+            orient: vec![0; orbit.size],
+            modulus: orbit.orientation,
+        }
+    }
+}
+
+/// State of a [`Puzzle`]: for each orbit, the piece at each position and
+/// its orientation. Multiplication applies the right state after the left
+/// one, as for [`Cube`](super::Cube).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PuzzleState {
+    orbits: Vec<OrbitState>,
+}
+
+impl PuzzleState {
+    /// Returns the piece at `position` of `orbit` and its orientation.
+    pub fn piece(&self, orbit: usize, position: usize) -> (usize, u8) {
+        let state = &self.orbits[orbit];
+        (state.perm[position], state.orient[position])
+    }
+
+    pub fn permutation(&self, orbit: usize) -> &[usize] {
+        &self.orbits[orbit].perm
+    }
+
+    pub fn orientation(&self, orbit: usize) -> &[u8] {
+        &self.orbits[orbit].orient
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.orbits.iter().all(|state| {
+            state.perm.iter().enumerate().all(|(i, p)| i == *p)
+                && state.orient.iter().all(|o| *o == 0)
+        })
+    }
+
+    pub fn inverse(&self) -> Self {
+        let orbits = self
+            .orbits
+            .iter()
+            .map(|state| {
+                let mut perm = vec![0; state.perm.len()];
+                let mut orient = vec![0; state.perm.len()];
+                for (i, p) in state.perm.iter().enumerate() {
+                    perm[*p] = i;
+                    orient[*p] = (state.modulus - state.orient[i]) % state.modulus;
+                }
+                OrbitState {
+                    perm,
+                    orient,
+                    modulus: state.modulus,
+                }
+            })
+            .collect();
+
+        Self { orbits }
+    }
+}
+
+impl Mul for &PuzzleState {
+    type Output = PuzzleState;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let orbits = self
+            .orbits
+            .iter()
+            .zip(&rhs.orbits)
+            .map(|(a, b)| OrbitState {
+                perm: b.perm.iter().map(|p| a.perm[*p]).collect(),
+                orient: b
+                    .perm
+                    .iter()
+                    .zip(&b.orient)
+                    .map(|(p, o)| ((a.orient[*p] as u16 + *o as u16) % a.modulus as u16) as u8)
+                    .collect(),
+                modulus: a.modulus,
+            })
+            .collect();
+
+        PuzzleState { orbits }
+    }
+}
+
+impl MulAssign<&PuzzleState> for PuzzleState {
+    fn mul_assign(&mut self, rhs: &PuzzleState) {
+        *self = &*self * rhs;
+    }
+}
+
+/// A power of a generator of a [`Puzzle`], named by [`Puzzle::move_name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PuzzleMove {
+    /// Index of the generator in the definition
+    pub generator: usize,
+    pub power: usize,
+    /// Order of the generator, so that the move can be inverted on its own
+    order: usize,
+}
+
+impl PuzzleMove {
+    pub fn inverse(&self) -> PuzzleMove {
+        PuzzleMove {
+            power: self.order - self.power,
+            ..*self
+        }
+    }
+}
+
+/// A permutation puzzle read from a definition of its orbits and generator
+/// moves.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    name: String,
+    orbits: Vec<Orbit>,
+    generators: Vec<String>,
+    /// All non-trivial powers of the generators, grouped by generator
+    moves: Vec<(PuzzleMove, PuzzleState)>,
+    automaton: MoveAutomaton,
+}
+
+impl Puzzle {
+    /// Reads the definition of a puzzle from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?;
+        text.parse()
+            .map_err(|e| anyhow!("invalid puzzle definition {}: {e}", path.display()))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn orbits(&self) -> &[Orbit] {
+        &self.orbits
+    }
+
+    pub fn generators(&self) -> &[String] {
+        &self.generators
+    }
+
+    /// Returns all moves: the powers of the generators.
+    pub fn moves(&self) -> impl Iterator<Item = PuzzleMove> + '_ {
+        self.moves.iter().map(|(m, _)| *m)
+    }
+
+    /// Returns the state reached by the move from the solved state.
+    pub fn move_state(&self, m: &PuzzleMove) -> &PuzzleState {
+        &self.moves[self.move_index(m)].1
+    }
+
+    /// Returns the name of the move, e.g. `R`, `R2` or `R'`.
+    pub fn move_name(&self, m: &PuzzleMove) -> String {
+        let generator = &self.generators[m.generator];
+        if m.power == 1 {
+            generator.clone()
+        } else if m.power == m.order - 1 {
+            format!("{generator}'")
+        } else {
+            format!("{generator}{}", m.power)
+        }
+    }
+
+    /// Formats the moves separated by spaces.
+    pub fn format_moves(&self, moves: &[PuzzleMove]) -> String {
+        moves
+            .iter()
+            .map(|m| self.move_name(m))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parses moves separated by whitespace, e.g. `R U' R2`.
+    pub fn parse_moves(&self, s: &str) -> Result<Vec<PuzzleMove>> {
+        s.split_whitespace()
+            .map(|token| {
+                self.moves()
+                    .find(|m| self.move_name(m) == token)
+                    .ok_or_else(|| anyhow!("{token} is not a move of {}", self.name))
+            })
+            .collect()
+    }
+
+    pub fn solved(&self) -> PuzzleState {
+        PuzzleState {
+            orbits: self.orbits.iter().map(OrbitState::identity).collect(),
+        }
+    }
+
+    /// Returns the state after applying the moves to `state`.
+    pub fn apply_moves(&self, state: &PuzzleState, moves: &[PuzzleMove]) -> PuzzleState {
+        moves
+            .iter()
+            .fold(state.clone(), |acc, m| &acc * self.move_state(m))
+    }
+
+    /// Returns the searchable node of `state`, whose goal is the solved
+    /// state.
+    pub fn node(&self, state: PuzzleState) -> PuzzleNode<'_> {
+        PuzzleNode {
+            puzzle: self,
+            state,
+            sequence: MoveAutomaton::START,
+        }
+    }
+
+    /// Returns the shortest moves solving `state` of at most `max_depth`
+    /// moves, searching from both the state and the solved state.
+    pub fn solve(&self, state: &PuzzleState, max_depth: usize) -> Option<Vec<PuzzleMove>> {
+        search::bidirectional_search(
+            self.node(state.clone()),
+            self.node(self.solved()),
+            max_depth,
+        )
+    }
+
+    /// Returns the moves solving `state` found by iterative deepening over
+    /// the canonical sequences of moves, which needs little memory but
+    /// takes exponential time in the length of the solution.
+    pub fn solve_iterative(
+        &self,
+        state: &PuzzleState,
+        max_depth: usize,
+    ) -> Option<Vec<PuzzleMove>> {
+        search::iterative_deepening_dfs(SearchNode::root(self.node(state.clone())), max_depth)
+            .map(|node| node.path())
+    }
+
+    fn move_index(&self, m: &PuzzleMove) -> usize {
+        self.moves
+            .iter()
+            .position(|(other, _)| other == m)
+            .expect("the move belongs to the puzzle")
+    }
+
+    fn from_entries(entries: &[Entry]) -> Result<Self> {
+        let mut name = None;
+        let mut orbits: Vec<Orbit> = vec![];
+        for entry in entries {
+            let error = |message: &str| anyhow!("line {}: {message}", entry.line);
+            match (entry.key.as_slice(), &entry.value) {
+                ([key], Value::String(value)) if key == "name" => name = Some(value.clone()),
+                ([table, orbit, field], Value::Integer(value)) if table == "orbits" => {
+                    let index = match orbits.iter().position(|o| &o.name == orbit) {
+                        Some(index) => index,
+                        None => {
+                            orbits.push(Orbit {
+                                name: orbit.clone(),
+                                size: 0,
+                                orientation: 1,
+                            });
+                            orbits.len() - 1
+                        }
+                    };
+                    match field.as_str() {
+                        "size" => {
+                            orbits[index].size = usize::try_from(*value)
+                                .ok()
+                                .filter(|size| *size > 0)
+                                .ok_or_else(|| error("the size must be positive"))?;
+                        }
+                        "orientation" => {
+                            orbits[index].orientation = u8::try_from(*value)
+                                .ok()
+                                .filter(|modulus| *modulus > 0)
+                                .ok_or_else(|| error("the orientation must be in 1..=255"))?;
+                        }
+                        _ => return Err(error(&format!("unknown orbit field {field}"))),
+                    }
+                }
+                ([table, ..], _) if table == "moves" => {}
+                _ => return Err(error(&format!("unexpected key {}", entry.key.join(".")))),
+            }
+        }
+
+        let name = name.ok_or_else(|| anyhow!("missing name"))?;
+        if orbits.is_empty() {
+            return Err(anyhow!("no orbits defined"));
+        }
+        if let Some(orbit) = orbits.iter().find(|o| o.size == 0) {
+            return Err(anyhow!("missing size of orbit {}", orbit.name));
+        }
+
+        let solved = PuzzleState {
+            orbits: orbits.iter().map(OrbitState::identity).collect(),
+        };
+        let mut generators: Vec<String> = vec![];
+        let mut states: Vec<PuzzleState> = vec![];
+        for entry in entries {
+            let error = |message: &str| anyhow!("line {}: {message}", entry.line);
+            let ([table, generator, orbit, field], Value::Array(values)) =
+                (entry.key.as_slice(), &entry.value)
+            else {
+                if entry.key[0] == "moves" {
+                    return Err(error("expected moves.<move>.<orbit>.<field> = [...]"));
+                }
+                continue;
+            };
+            if table != "moves" {
+                continue;
+            }
+
+            let index = match generators.iter().position(|g| g == generator) {
+                Some(index) => index,
+                None => {
+                    generators.push(generator.clone());
+                    states.push(solved.clone());
+                    generators.len() - 1
+                }
+            };
+            let o = orbits
+                .iter()
+                .position(|o| &o.name == orbit)
+                .ok_or_else(|| error(&format!("unknown orbit {orbit}")))?;
+            let state = &mut states[index].orbits[o];
+            if values.len() != orbits[o].size {
+                return Err(error(&format!(
+                    "expected {} values for orbit {orbit}",
+                    orbits[o].size
+                )));
+            }
+
+            match field.as_str() {
+                "permutation" => {
+                    let mut seen = vec![false; values.len()];
+                    for value in values {
+                        let valid = usize::try_from(*value).is_ok_and(|p| {
+                            p < seen.len() && !std::mem::replace(&mut seen[p], true)
+                        });
+                        if !valid {
+                            return Err(error(&format!("{values:?} is not a permutation")));
+                        }
+                    }
+                    state.perm = values.iter().map(|p| *p as usize).collect();
+                }
+                "orientation" => {
+                    let modulus = orbits[o].orientation;
+                    if values.iter().any(|v| !(0..modulus as i64).contains(v)) {
+                        return Err(error(&format!("orientations must be less than {modulus}")));
+                    }
+                    state.orient = values.iter().map(|v| *v as u8).collect();
+                }
+                _ => return Err(error(&format!("unknown move field {field}"))),
+            }
+        }
+
+        if generators.is_empty() {
+            return Err(anyhow!("no moves defined"));
+        }
+
+        let mut moves = vec![];
+        for (index, (generator, state)) in generators.iter().zip(&states).enumerate() {
+            if state.is_identity() {
+                return Err(anyhow!("move {generator} does nothing"));
+            }
+
+            let mut powers = vec![state.clone()];
+            while !powers.last().unwrap().is_identity() {
+                // a generator can have a huge order, so the powers are
+                // counted as they are found
+                if moves.len() + powers.len() >= MAX_MOVES {
+                    return Err(anyhow!("too many moves (at most {MAX_MOVES})"));
+                }
+                powers.push(powers.last().unwrap() * state);
+            }
+            let order = powers.len();
+            powers.pop();
+            moves.extend(powers.into_iter().enumerate().map(|(i, power)| {
+                let m = PuzzleMove {
+                    generator: index,
+                    power: i + 1,
+                    order,
+                };
+                (m, power)
+            }));
+        }
+
+        let products = |a: usize, b: usize| &moves[a].1 * &moves[b].1;
+        let automaton = MoveAutomaton::new(
+            moves.len(),
+            |a, b| products(a, b) == products(b, a),
+            |a, b| {
+                let product = products(a, b);
+                product.is_identity() || moves.iter().any(|(_, state)| *state == product)
+            },
+        );
+
+        Ok(Self {
+            name,
+            orbits,
+            generators,
+            moves,
+            automaton,
+        })
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_entries(&definition::parse(s)?)
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let orbits = self
+            .orbits
+            .iter()
+            .map(|o| format!("{} {}x{}", o.name, o.size, o.orientation))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "{} ({orbits}; moves {})",
+            self.name,
+            self.generators.join(" ")
+        )
+    }
+}
+
+/// A state of a [`Puzzle`] searched for the solved state.
+///
+/// As a [`State`], it offers only the canonical sequences of moves, so it
+/// can be searched by iterative deepening without repeating equal
+/// sequences. As [`Invertible`], it offers all moves for bidirectional
+/// search.
+#[derive(Debug, Clone)]
+pub struct PuzzleNode<'a> {
+    puzzle: &'a Puzzle,
+    state: PuzzleState,
+    /// State of the automaton of canonical sequences after the path
+    sequence: usize,
+}
+
+impl PuzzleNode<'_> {
+    pub fn state(&self) -> &PuzzleState {
+        &self.state
+    }
+}
+
+impl State for PuzzleNode<'_> {
+    type Move = PuzzleMove;
+
+    fn is_goal(&self) -> bool {
+        self.state.is_identity()
+    }
+
+    fn moves(&self, _last: Option<&PuzzleMove>) -> impl IntoIterator<Item = (PuzzleMove, Self)> {
+        self.puzzle
+            .automaton
+            .moves(self.sequence)
+            .map(|(i, sequence)| {
+                let (m, state) = &self.puzzle.moves[i];
+                let node = PuzzleNode {
+                    puzzle: self.puzzle,
+                    state: &self.state * state,
+                    sequence,
+                };
+                (*m, node)
+            })
+    }
+}
+
+impl Prunable for PuzzleNode<'_> {
+    fn to_prune(&self, _depth: usize, _max_depth: usize) -> bool {
+        false
+    }
+}
+
+impl Keyed for PuzzleNode<'_> {
+    type Key = PuzzleState;
+
+    fn key(&self) -> PuzzleState {
+        self.state.clone()
+    }
+}
+
+impl Searchable for PuzzleNode<'_> {
+    fn is_goal(&self) -> bool {
+        self.state.is_identity()
+    }
+
+    fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
+        Some(self.transitions().into_iter().map(|(_, node)| node))
+    }
+}
+
+impl Invertible for PuzzleNode<'_> {
+    type Move = PuzzleMove;
+
+    fn transitions(&self) -> impl IntoIterator<Item = (PuzzleMove, Self)> {
+        self.puzzle.moves.iter().map(|(m, state)| {
+            let node = PuzzleNode {
+                puzzle: self.puzzle,
+                state: &self.state * state,
+                sequence: MoveAutomaton::START,
+            };
+            (*m, node)
+        })
+    }
+
+    fn inverse(m: &PuzzleMove) -> PuzzleMove {
+        m.inverse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operation;
+
+    const CUBE: &str = include_str!("../../../puzzles/3x3x3.toml");
+    const POCKET_CUBE: &str = include_str!("../../../puzzles/2x2x2.toml");
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let puzzle: Puzzle = CUBE.parse()?;
+
+        assert_eq!(puzzle.name(), "3x3x3");
+        assert_eq!(puzzle.orbits().len(), 2);
+        assert_eq!(puzzle.generators(), ["U", "D", "L", "R", "F", "B"]);
+        assert_eq!(puzzle.moves().count(), 18);
+
+        let moves = puzzle.parse_moves("R U2 F'")?;
+        assert_eq!(puzzle.format_moves(&moves), "R U2 F'");
+        assert_eq!(moves[2].inverse(), puzzle.parse_moves("F")?[0]);
+        assert!(puzzle.parse_moves("R3").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let orbit = "name = \"Toy\"\n[orbits.a]\nsize = 3\norientation = 2\n";
+        let invalid = [
+            "[orbits.a]\nsize = 3\n[moves.X]\na.permutation = [1, 2, 0]",
+            "name = \"Toy\"\n[moves.X]\na.permutation = [1, 2, 0]",
+            &format!("{orbit}[moves.X]\na.permutation = [1, 1, 0]"),
+            &format!("{orbit}[moves.X]\na.permutation = [1, 0]"),
+            &format!("{orbit}[moves.X]\na.orientation = [0, 2, 0]"),
+            &format!("{orbit}[moves.X]\nb.permutation = [1, 2, 0]"),
+            &format!("{orbit}[moves.X]\na.permutation = [0, 1, 2]"),
+            &format!("{orbit}[moves.X]\na.cycles = [1, 2, 0]"),
+        ];
+
+        for text in invalid {
+            assert!(text.parse::<Puzzle>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_large_orders() -> anyhow::Result<()> {
+        // a 7-cycle and a 13-cycle: 90 powers
+        let mut permutation = (1..7).chain([0]).collect::<Vec<_>>();
+        permutation.extend((8..20).chain([7]));
+        let text = format!(
+            "name = \"Toy\"\n[orbits.a]\nsize = 20\n[moves.X]\na.permutation = {permutation:?}"
+        );
+        assert!(text.parse::<Puzzle>().is_err());
+
+        // orientations near the modulus add up beyond 255
+        let text = "name = \"Toy\"\n[orbits.a]\nsize = 2\norientation = 200\n\
+                    [moves.X]\na.permutation = [1, 0]\na.orientation = [150, 150]";
+        let puzzle: Puzzle = text.parse()?;
+        let x = puzzle.parse_moves("X")?;
+        let state = puzzle.apply_moves(&puzzle.solved(), &[x[0], x[0]]);
+        assert_eq!(state.orientation(0), [100, 100]);
+        assert_eq!(puzzle.moves().count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_cube() -> anyhow::Result<()> {
+        let puzzle: Puzzle = CUBE.parse()?;
+        let scramble = "R U' F2 L D B' R2 U F L2 D' B";
+        let state = puzzle.apply_moves(&puzzle.solved(), &puzzle.parse_moves(scramble)?);
+        let rotation = Operation::parse_sequence(scramble)?
+            .iter()
+            .fold(crate::Cube::new().rotation, |acc, op| &acc * op.rotation());
+
+        for i in 0..12 {
+            assert_eq!(state.piece(0, i), rotation.edge(i));
+        }
+        for i in 0..8 {
+            assert_eq!(state.piece(1, i), rotation.corner(i));
+        }
+        assert!((&state * &state.inverse()).is_identity());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve() -> anyhow::Result<()> {
+        let puzzle: Puzzle = POCKET_CUBE.parse()?;
+        let scramble = puzzle.parse_moves("R U F' R2 U' F R'")?;
+        let state = puzzle.apply_moves(&puzzle.solved(), &scramble);
+
+        let solution = puzzle.solve(&state, 11).unwrap();
+        assert!(solution.len() <= scramble.len());
+        assert!(puzzle.apply_moves(&state, &solution).is_identity());
+
+        let short = puzzle.apply_moves(&puzzle.solved(), &puzzle.parse_moves("R U' F2 R")?);
+        let solution = puzzle.solve_iterative(&short, 4).unwrap();
+        assert_eq!(solution.len(), 4);
+        assert!(puzzle.apply_moves(&short, &solution).is_identity());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_sequences() -> anyhow::Result<()> {
+        let puzzle: Puzzle = CUBE.parse()?;
+        let [r, l] = [puzzle.parse_moves("R")?[0], puzzle.parse_moves("L")?[0]];
+        let node = puzzle.node(puzzle.solved());
+        let after_r = node
+            .moves(None)
+            .into_iter()
+            .find(|(m, _)| *m == r)
+            .unwrap()
+            .1;
+
+        let next = after_r
+            .moves(Some(&r))
+            .into_iter()
+            .map(|(m, _)| m)
+            .collect::<Vec<_>>();
+        // R is not followed by another R turn, nor by L which commutes with it
+        assert_eq!(next.len(), 12);
+        assert!(!next.contains(&l));
+
+        Ok(())
+    }
+}
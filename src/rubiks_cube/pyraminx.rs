@@ -0,0 +1,417 @@
+use std::fmt::Display;
+use std::ops::Mul;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+use rand::prelude::*;
+
+use super::algebra::quotient_group_vec::QuotientGroupVec;
+use super::algebra::symmetric_group::SymmetricGroup;
+use super::enumeration::{self, Enumeration};
+use super::table_cache;
+
+/// Corners of the Pyraminx, around which its layers turn: the top, and the
+/// left, right and back corners of the bottom face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PyraminxAxis {
+    U,
+    L,
+    R,
+    B,
+}
+
+impl PyraminxAxis {
+    pub fn all() -> [PyraminxAxis; 4] {
+        [
+            PyraminxAxis::U,
+            PyraminxAxis::L,
+            PyraminxAxis::R,
+            PyraminxAxis::B,
+        ]
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Move of the Pyraminx: a turn of the two layers at a corner by a third,
+/// clockwise when looking at the corner, written `U` or `U'`, or a turn of
+/// the tip alone, written in lowercase as `u` or `u'`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyraminxMove {
+    pub axis: PyraminxAxis,
+    pub tip: bool,
+    pub clockwise: bool,
+}
+
+impl PyraminxMove {
+    /// Returns the turns of the layers followed by the turns of the tips.
+    pub fn all() -> Vec<PyraminxMove> {
+        [false, true]
+            .into_iter()
+            .flat_map(|tip| {
+                PyraminxAxis::all().into_iter().flat_map(move |axis| {
+                    [true, false].map(|clockwise| PyraminxMove {
+                        axis,
+                        tip,
+                        clockwise,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the move which cancels this move.
+    pub fn inverse(&self) -> PyraminxMove {
+        PyraminxMove {
+            clockwise: !self.clockwise,
+            ..*self
+        }
+    }
+
+    /// Parses moves separated by whitespace, e.g. `R U' L b'`.
+    pub fn parse_sequence(s: &str) -> Result<Vec<PyraminxMove>> {
+        s.split_whitespace().map(str::parse).collect()
+    }
+
+    /// Index of the move in [`PyraminxMove::all`].
+    fn index(&self) -> usize {
+        self.tip as usize * 8 + self.axis.index() * 2 + !self.clockwise as usize
+    }
+}
+
+impl FromStr for PyraminxMove {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or_else(|| anyhow!("empty Pyraminx move"))?;
+        let axis = match letter.to_ascii_uppercase() {
+            'U' => PyraminxAxis::U,
+            'L' => PyraminxAxis::L,
+            'R' => PyraminxAxis::R,
+            'B' => PyraminxAxis::B,
+            _ => return Err(anyhow!("invalid Pyraminx move: {s}")),
+        };
+        let clockwise = match chars.as_str() {
+            "" => true,
+            "'" => false,
+            _ => return Err(anyhow!("invalid Pyraminx move: {s}")),
+        };
+
+        Ok(PyraminxMove {
+            axis,
+            tip: letter.is_ascii_lowercase(),
+            clockwise,
+        })
+    }
+}
+
+impl Display for PyraminxMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let letter = format!("{:?}", self.axis);
+        if self.tip {
+            write!(f, "{}", letter.to_lowercase())?;
+        } else {
+            write!(f, "{letter}")?;
+        }
+        if !self.clockwise {
+            write!(f, "'")?;
+        }
+        Ok(())
+    }
+}
+
+/// Edges at each position after the clockwise turn of U, L, R and B, with
+/// the edges numbered UL, UR, UB, LR, RB, BL.
+const EDGE_PERMS: [[usize; 6]; 4] = [
+    [1, 2, 0, 3, 4, 5],
+    [5, 1, 2, 0, 4, 3],
+    [0, 3, 2, 4, 1, 5],
+    [0, 1, 4, 3, 5, 2],
+];
+
+/// Edge orientations after the clockwise turns. The reference sticker of
+/// an edge is on the face opposite the first corner of U, L, R, B which is
+/// not an end of the edge.
+const EDGE_ORIENTS: [[u8; 6]; 4] = [
+    [1, 1, 0, 0, 0, 0],
+    [0, 0, 0, 1, 0, 1],
+    [0, 1, 0, 1, 0, 0],
+    [0, 0, 0, 0, 1, 1],
+];
+
+/// States after each move of [`PyraminxMove::all`] from the solved state.
+static MOVES: LazyLock<Vec<Pyraminx>> = LazyLock::new(|| {
+    PyraminxMove::all()
+        .iter()
+        .map(|m| {
+            let axis = m.axis.index();
+            let turn = if m.clockwise { 1 } else { 2 };
+            let mut twist = [0; 4];
+            twist[axis] = turn;
+
+            let mut state = Pyraminx {
+                tips: twist.into(),
+                ..Default::default()
+            };
+            if !m.tip {
+                let perm: SymmetricGroup<6> = EDGE_PERMS[axis].try_into().unwrap();
+                let orient: QuotientGroupVec<2, 6> = EDGE_ORIENTS[axis].into();
+                // the counterclockwise turn is the clockwise turn twice
+                let (perm, orient) = if m.clockwise {
+                    (perm, orient)
+                } else {
+                    let twice_orient = perm.permute(&orient.into()).into();
+                    (&perm * &perm, &twice_orient + &orient)
+                };
+                state.edge_perm = perm;
+                state.edge_orient = orient;
+                state.centers = twist.into();
+            }
+            state
+        })
+        .collect()
+});
+
+type EdgeState = (SymmetricGroup<6>, QuotientGroupVec<2, 6>);
+
+/// Edge states reachable by the turns of the layers.
+static EDGES: LazyLock<Enumeration<EdgeState>> = LazyLock::new(|| {
+    Enumeration::new(Pyraminx::default().edges(), 8, |edges, m| {
+        let state = Pyraminx {
+            edge_perm: edges.0.clone(),
+            edge_orient: edges.1,
+            ..Default::default()
+        };
+        (&state * &MOVES[m]).edges()
+    })
+});
+
+/// Center orientations, all reachable independently of the edges.
+static CENTERS: LazyLock<Enumeration<QuotientGroupVec<3, 4>>> = LazyLock::new(|| {
+    Enumeration::new(QuotientGroupVec::default(), 8, |centers, m| {
+        centers + &MOVES[m].centers
+    })
+});
+
+/// Distances of all states of the layers to the solved state, ignoring the
+/// tips.
+static TABLE: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let size = EDGES.len() * CENTERS.len();
+    // bump the version whenever the numbering of the states changes
+    table_cache::load_or_generate(
+        "pyraminx",
+        table_cache::fnv1a(b"pyraminx v1 U L R B"),
+        || enumeration::distances(&EDGES, &CENTERS),
+        |table| table.clone(),
+        |bytes| (bytes.len() == size).then(|| bytes.to_vec()),
+    )
+});
+
+/// Represents Pyraminx state by the permutation and orientation of the 6
+/// edges and the orientations of the 4 centers below the tips and of the 4
+/// tips, counted in clockwise thirds and ordered as [`PyraminxAxis`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Pyraminx {
+    edge_perm: SymmetricGroup<6>,
+    edge_orient: QuotientGroupVec<2, 6>,
+    centers: QuotientGroupVec<3, 4>,
+    tips: QuotientGroupVec<3, 4>,
+}
+
+impl Pyraminx {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a uniformly random state.
+    pub fn random() -> Self {
+        let mut rng = rand::rng();
+        let (edge_perm, edge_orient) = EDGES.random().clone();
+        let centers = *CENTERS.random();
+        let tips = [0; 4].map(|_| rng.random_range(0..3)).into();
+
+        Self {
+            edge_perm,
+            edge_orient,
+            centers,
+            tips,
+        }
+    }
+
+    /// Returns a uniformly random state with the shortest moves reaching it
+    /// from the solved state, the tips being turned last.
+    pub fn scramble() -> (Self, Vec<PyraminxMove>) {
+        let pyraminx = Self::random();
+        let moves = enumeration::scramble(&pyraminx.solve(), PyraminxMove::inverse);
+
+        (pyraminx, moves)
+    }
+
+    /// Returns the edge piece at `position` and its orientation.
+    pub fn edge(&self, position: usize) -> (usize, u8) {
+        (self.edge_perm[position], self.edge_orient[position].into())
+    }
+
+    pub fn center(&self, axis: PyraminxAxis) -> u8 {
+        self.centers[axis.index()].into()
+    }
+
+    pub fn tip(&self, axis: PyraminxAxis) -> u8 {
+        self.tips[axis.index()].into()
+    }
+
+    pub fn apply_move(&mut self, m: &PyraminxMove) {
+        *self = self.move_applied(m);
+    }
+
+    pub fn move_applied(&self, m: &PyraminxMove) -> Self {
+        self * &MOVES[m.index()]
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the number of moves of the shortest solution: the distance
+    /// of the layers and one move for each tip which is not turned along
+    /// with its center.
+    pub fn distance(&self) -> usize {
+        self.layer_distance() + self.tip_moves().len()
+    }
+
+    /// Returns the shortest moves solving the Pyraminx: the tips first,
+    /// since turning the layers never changes the twist of a tip relative
+    /// to its center, then the layers, whose table is generated on first
+    /// use.
+    pub fn solve(&self) -> Vec<PyraminxMove> {
+        let mut result = self.tip_moves();
+        let mut pyraminx = self.clone();
+        for m in &result {
+            pyraminx.apply_move(m);
+        }
+
+        let layer_moves = PyraminxMove::all()
+            .into_iter()
+            .filter(|m| !m.tip)
+            .collect::<Vec<_>>();
+        result.extend(enumeration::descend(
+            &pyraminx,
+            &layer_moves,
+            Self::layer_distance,
+            Self::move_applied,
+        ));
+
+        result
+    }
+
+    fn edges(&self) -> EdgeState {
+        (self.edge_perm.clone(), self.edge_orient)
+    }
+
+    fn layer_distance(&self) -> usize {
+        let edges = EDGES
+            .index(&self.edges())
+            .expect("all edge states are reachable");
+        let centers = CENTERS.index(&self.centers).unwrap();
+        TABLE[edges * CENTERS.len() + centers] as usize
+    }
+
+    /// Returns the tip moves which align the tips with their centers.
+    fn tip_moves(&self) -> Vec<PyraminxMove> {
+        PyraminxAxis::all()
+            .into_iter()
+            .filter_map(|axis| {
+                let twist = (3 + self.tip(axis) - self.center(axis)) % 3;
+                (twist != 0).then_some(PyraminxMove {
+                    axis,
+                    tip: true,
+                    clockwise: twist == 2,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Mul for &Pyraminx {
+    type Output = Pyraminx;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let perm_orient = rhs.edge_perm.permute(&self.edge_orient.into()).into();
+
+        Pyraminx {
+            edge_perm: &self.edge_perm * &rhs.edge_perm,
+            edge_orient: &perm_orient + &rhs.edge_orient,
+            centers: &self.centers + &rhs.centers,
+            tips: &self.tips + &rhs.tips,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::Puzzle;
+
+    #[test]
+    fn test_parse_sequence() -> anyhow::Result<()> {
+        let moves = PyraminxMove::parse_sequence("R U' l b'")?;
+        let formatted = moves.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(formatted, ["R", "U'", "l", "b'"]);
+        assert!(PyraminxMove::parse_sequence("R2").is_err());
+        assert!(PyraminxMove::parse_sequence("F").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_size() {
+        // 6!/2 edge permutations, 2^5 edge and 3^4 center orientations
+        assert_eq!(EDGES.len(), 11520);
+        assert_eq!(CENTERS.len(), 81);
+        assert!(TABLE.iter().all(|d| *d <= 11));
+    }
+
+    #[test]
+    fn test_matches_definition() -> anyhow::Result<()> {
+        let puzzle: Puzzle = include_str!("../../puzzles/pyraminx.toml").parse()?;
+        let scramble = "U L' R B' U' R l b' r";
+        let state = puzzle.apply_moves(&puzzle.solved(), &puzzle.parse_moves(scramble)?);
+        let mut pyraminx = Pyraminx::new();
+        for m in PyraminxMove::parse_sequence(scramble)? {
+            pyraminx.apply_move(&m);
+        }
+
+        for i in 0..6 {
+            assert_eq!(state.piece(0, i), pyraminx.edge(i));
+        }
+        for (i, axis) in PyraminxAxis::all().into_iter().enumerate() {
+            assert_eq!(state.piece(1, i).1, pyraminx.center(axis));
+            assert_eq!(state.piece(2, i).1, pyraminx.tip(axis));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve() -> anyhow::Result<()> {
+        let mut pyraminx = Pyraminx::new();
+        for m in PyraminxMove::parse_sequence("U R' L B u' r")? {
+            pyraminx.apply_move(&m);
+        }
+
+        let solution = pyraminx.solve();
+        for m in &solution {
+            pyraminx.apply_move(m);
+        }
+
+        assert_eq!(solution.len(), 6);
+        assert!(pyraminx.is_solved());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,594 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+
+use super::algebra::rotation::Rotation;
+use super::{BigCube, BigMove, Cube, Face, solve_thistlethwaite, table_cache};
+
+type Cube4 = BigCube<4>;
+
+/// Slots of the centers of the 4x4x4 cube holding `colors` as a bitmask.
+fn color_mask(cube: &Cube4, colors: &[Face]) -> u32 {
+    (0..24)
+        .filter(|p| colors.contains(&cube.center_color(0, *p)))
+        .fold(0, |mask, p| mask | 1 << p)
+}
+
+/// Slots of the centers on `faces` as a bitmask.
+fn face_mask(faces: &[Face]) -> u32 {
+    let cube = Cube4::new();
+    (0..24)
+        .filter(|p| faces.contains(&cube.center_face(0, *p)))
+        .fold(0, |mask, p| mask | 1 << p)
+}
+
+/// Binomial coefficients `C(n, k)` for `n` up to 24.
+static BINOMIAL: [[u32; 25]; 25] = {
+    let mut table = [[0; 25]; 25];
+    let mut n = 0;
+    while n < 25 {
+        table[n][0] = 1;
+        let mut k = 1;
+        while k <= n {
+            table[n][k] = table[n - 1][k - 1] + table[n - 1][k];
+            k += 1;
+        }
+        n += 1;
+    }
+    table
+};
+
+/// Masks of the parts of a stage, of which there are at most three.
+type Masks = [u32; 3];
+
+/// Centers of some colors restricted to some slots: the colors are kept in
+/// `domain` by the moves of the stage and solved when they fill `target`.
+#[derive(Debug)]
+struct Part {
+    colors: Vec<Face>,
+    domain: u32,
+    target: u32,
+    /// Number of subsets of the domain which the colors may fill.
+    size: usize,
+    /// Terms of the rank added by each byte of a mask, by the number of
+    /// colors in the lower bytes.
+    ranks: [[[u32; 9]; 256]; 3],
+}
+
+impl Part {
+    fn new(colors: &[Face], domain: &[Face]) -> Self {
+        let domain = face_mask(domain);
+        let ranks = std::array::from_fn(|k| {
+            std::array::from_fn(|b| {
+                std::array::from_fn(|lower| {
+                    let bits = (0..8).filter(|bit| b >> bit & 1 == 1);
+                    (lower + 1..)
+                        .zip(bits)
+                        .map(|(i, bit)| {
+                            let below = domain & ((1 << (8 * k + bit)) - 1);
+                            BINOMIAL[below.count_ones() as usize].get(i).unwrap_or(&0)
+                        })
+                        .sum()
+                })
+            })
+        });
+
+        let target = face_mask(colors);
+
+        Self {
+            colors: colors.to_vec(),
+            domain,
+            target,
+            size: BINOMIAL[domain.count_ones() as usize][target.count_ones() as usize] as usize,
+            ranks,
+        }
+    }
+
+    /// Returns the rank of the slots holding the colors among the subsets
+    /// of the domain of the same size, in colexicographic order.
+    fn rank(&self, mask: u32) -> usize {
+        let bytes = [mask & 0xff, mask >> 8 & 0xff, mask >> 16];
+        let first = bytes[0].count_ones() as usize;
+        let second = first + bytes[1].count_ones() as usize;
+
+        (self.ranks[0][bytes[0] as usize][0]
+            + self.ranks[1][bytes[1] as usize][first.min(8)]
+            + self.ranks[2][bytes[2] as usize][second.min(8)]) as usize
+    }
+}
+
+/// A move acting on masks of center slots, byte by byte.
+#[derive(Debug)]
+struct MaskMove {
+    bytes: [[u32; 256]; 3],
+}
+
+impl MaskMove {
+    fn new(m: &BigMove) -> Self {
+        let cube = Cube4::new().move_applied(m);
+        let mut destination = [0; 24];
+        for p in 0..24 {
+            destination[cube.center(0, p)] = p;
+        }
+
+        let bytes = std::array::from_fn(|k| {
+            std::array::from_fn(|b| {
+                (0..8)
+                    .filter(|bit| b >> bit & 1 == 1)
+                    .fold(0, |mask, bit| mask | 1 << destination[8 * k + bit])
+            })
+        });
+
+        Self { bytes }
+    }
+
+    fn apply(&self, mask: u32) -> u32 {
+        self.bytes[0][(mask & 0xff) as usize]
+            | self.bytes[1][(mask >> 8 & 0xff) as usize]
+            | self.bytes[2][(mask >> 16) as usize]
+    }
+}
+
+/// A stage of solving the centers of the 4x4x4 cube with the moves which
+/// keep the centers solved by the earlier stages, with the distances of all
+/// the states of the stage to its goal.
+struct CenterStage {
+    moves: Vec<BigMove>,
+    mask_moves: Vec<MaskMove>,
+    parts: Vec<Part>,
+    table: Vec<u8>,
+}
+
+impl CenterStage {
+    /// Loads the distance table of the stage from the table directory, or
+    /// generates it.
+    fn new(name: &str, moves: &str, parts: Vec<Part>) -> Self {
+        let moves = BigMove::parse_sequence(moves).unwrap();
+        let mask_moves = moves.iter().map(MaskMove::new).collect::<Vec<_>>();
+        let mut stage = Self {
+            moves,
+            mask_moves,
+            parts,
+            table: vec![],
+        };
+
+        // bump the version whenever the coordinates change
+        let description = format!(
+            "reduction {name} v1 {}",
+            stage
+                .moves
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let size = stage.size();
+        stage.table = table_cache::load_or_generate(
+            &format!("reduction-{name}"),
+            table_cache::fnv1a(description.as_bytes()),
+            || stage.generate(),
+            |table| table.clone(),
+            |bytes| (bytes.len() == size).then(|| bytes.to_vec()),
+        );
+
+        stage
+    }
+
+    fn size(&self) -> usize {
+        self.parts.iter().map(|part| part.size).product()
+    }
+
+    fn index(&self, masks: &Masks) -> usize {
+        let mut index = 0;
+        for (i, part) in self.parts.iter().enumerate() {
+            index = index * part.size + part.rank(masks[i]);
+        }
+        index
+    }
+
+    fn masks(&self, cube: &Cube4) -> Masks {
+        let mut masks = [0; 3];
+        for (mask, part) in masks.iter_mut().zip(&self.parts) {
+            *mask = color_mask(cube, &part.colors);
+        }
+        masks
+    }
+
+    /// Generates the distances by breadth-first search from the goal.
+    fn generate(&self) -> Vec<u8> {
+        let mut table = vec![u8::MAX; self.size()];
+        let mut goal = [0; 3];
+        for (mask, part) in goal.iter_mut().zip(&self.parts) {
+            *mask = part.target;
+        }
+        table[self.index(&goal)] = 0;
+
+        let mut queue = VecDeque::from([goal]);
+        while let Some(masks) = queue.pop_front() {
+            let distance = table[self.index(&masks)];
+            for m in &self.mask_moves {
+                let next = [m.apply(masks[0]), m.apply(masks[1]), m.apply(masks[2])];
+                let index = self.index(&next);
+                if table[index] == u8::MAX {
+                    table[index] = distance + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        table
+    }
+
+    fn distance(&self, cube: &Cube4) -> Option<u8> {
+        let masks = self.masks(cube);
+        if masks
+            .iter()
+            .zip(&self.parts)
+            .any(|(mask, part)| mask & !part.domain != 0)
+        {
+            return None;
+        }
+
+        Some(self.table[self.index(&masks)]).filter(|d| *d != u8::MAX)
+    }
+
+    /// Returns the shortest moves reaching the goal of the stage, following
+    /// moves which decrease the distance.
+    fn solve(&self, cube: &Cube4) -> Option<Vec<BigMove>> {
+        let mut cube = cube.clone();
+        let mut distance = self.distance(&cube)?;
+        let mut result = vec![];
+
+        while distance > 0 {
+            let (m, next) = self
+                .moves
+                .iter()
+                .map(|m| (*m, cube.move_applied(m)))
+                .find(|(_, next)| self.distance(next) == Some(distance - 1))?;
+            result.push(m);
+            cube = next;
+            distance -= 1;
+        }
+
+        Some(result)
+    }
+}
+
+use Face::*;
+
+/// Brings the U and D centers to the U and D faces.
+static CENTERS1: LazyLock<CenterStage> = LazyLock::new(|| {
+    CenterStage::new(
+        "centers1",
+        "R R2 R' L L2 L' U U2 U' D D2 D' F F2 F' B B2 B' \
+         Rw Rw2 Rw' Lw Lw2 Lw' Uw Uw2 Uw' Dw Dw2 Dw' Fw Fw2 Fw' Bw Bw2 Bw'",
+        vec![Part::new(&[U, D], &[U, R, F, D, L, B])],
+    )
+});
+
+/// Solves the U and D centers and brings the F and B centers to the F and
+/// B faces.
+static CENTERS2: LazyLock<CenterStage> = LazyLock::new(|| {
+    CenterStage::new(
+        "centers2",
+        "R R2 R' L L2 L' U U2 U' D D2 D' F F2 F' B B2 B' \
+         Rw2 Lw2 Uw Uw2 Uw' Dw Dw2 Dw' Fw2 Bw2",
+        vec![Part::new(&[U], &[U, D]), Part::new(&[F, B], &[R, F, L, B])],
+    )
+});
+
+/// Solves the remaining centers.
+static CENTERS3: LazyLock<CenterStage> = LazyLock::new(|| {
+    CenterStage::new(
+        "centers3",
+        "R R2 R' L L2 L' U U2 U' D D2 D' F F2 F' B B2 B' Rw2 Lw2 Uw2 Dw2 Fw2 Bw2",
+        vec![
+            Part::new(&[U], &[U, D]),
+            Part::new(&[F], &[F, B]),
+            Part::new(&[R], &[R, L]),
+        ],
+    )
+});
+
+/// Wings of the 4x4x4 cube placed at each position.
+type Wings = [usize; 24];
+
+fn wings(cube: &Cube4) -> Wings {
+    std::array::from_fn(|p| cube.wing(0, p).0)
+}
+
+/// Returns the wings after the moves leading to `b` played after the moves
+/// leading to `a`.
+fn compose(a: &Wings, b: &Wings) -> Wings {
+    std::array::from_fn(|p| a[b[p]])
+}
+
+/// Edges whose two wings belong together. Two wings of an edge at the same
+/// edge position always have the same orientation, so that they are
+/// paired as an edge of the 3x3x3 cube.
+fn paired_edges(wings: &Wings) -> usize {
+    (0..12)
+        .filter(|edge| wings[2 * edge] / 2 == wings[2 * edge + 1] / 2)
+        .count()
+}
+
+/// Moves with the wings they lead to from the solved state.
+type Algorithm = (Vec<BigMove>, Wings);
+
+/// Algorithms pairing the wings at the left and right of the front layer
+/// of a slice with face turns: the slice brings a wing next to its
+/// partner, the face turns swap the pair with an edge of the U layer and
+/// the slice goes back. Only those keeping the centers are used.
+static PAIRING: LazyLock<Vec<Algorithm>> = LazyLock::new(|| {
+    let flips = [
+        "R U R' F R' F' R",
+        "L' U' L F' L F L'",
+        "R F' U R' F",
+        "L' F U' L F'",
+    ];
+    let mut result = vec![];
+    for slice in ["Uw", "Uw'", "Dw", "Dw'"] {
+        let slice = slice.parse::<BigMove>().unwrap();
+        for flip in flips {
+            let flip = BigMove::parse_sequence(flip).unwrap();
+            let inverse = flip.iter().rev().map(|m| m.inverse()).collect();
+            for flip in [flip, inverse] {
+                let (moves, cube) = algorithm([vec![slice], flip, vec![slice.inverse()]].concat());
+                if cube.are_centers_solved() {
+                    result.push((moves, wings(&cube)));
+                }
+            }
+        }
+    }
+    result
+});
+
+/// Face turns bringing edges to the places where the algorithms pair them,
+/// grouped by their number.
+static SETUPS: LazyLock<Vec<Vec<Algorithm>>> = LazyLock::new(|| {
+    let turns = Face::all()
+        .into_iter()
+        .flat_map(|face| (1..=3).map(move |turns| BigMove::face_turn(face, turns).unwrap()))
+        .map(|m| (m, wings(&Cube4::new().move_applied(&m))))
+        .collect::<Vec<_>>();
+    let mut result = vec![vec![(vec![], wings(&Cube4::new()))]];
+    for length in 1..=3 {
+        let mut setups = vec![];
+        for (setup, wings) in &result[length - 1] {
+            for (m, turn) in &turns {
+                if setup
+                    .last()
+                    .is_some_and(|last: &BigMove| last.face() == m.face())
+                {
+                    continue;
+                }
+                let mut moves = setup.clone();
+                moves.push(*m);
+                setups.push((moves, compose(wings, turn)));
+            }
+        }
+        result.push(setups);
+    }
+    result
+});
+
+/// Flips the UF edge by swapping its wings, keeping the centers.
+const OLL_PARITY: &str = "Rw2 B2 U2 Lw U2 Rw' U2 Rw U2 F2 Rw F2 Lw' B2 Rw2";
+
+/// Swaps the UF and UB edges, keeping the centers and turning the corners
+/// of U by a half turn.
+const PLL_PARITY: &str = "Rw2 R2 U2 Rw2 R2 Uw2 Rw2 R2 Uw2";
+
+/// Returns the moves with the state they lead to from the solved state.
+fn algorithm(moves: Vec<BigMove>) -> (Vec<BigMove>, Cube4) {
+    let mut cube = Cube4::new();
+    cube.apply_moves(&moves);
+    (moves, cube)
+}
+
+/// Pairs the wings into edges, one or two edges at a time, with the
+/// shortest combination of a setup and a pairing algorithm which pairs
+/// more edges. The centers are kept solved.
+fn pair_edges(cube: &Cube4) -> Result<Vec<BigMove>> {
+    let mut cube = cube.clone();
+    let mut result = vec![];
+    let mut paired = paired_edges(&wings(&cube));
+
+    while paired < 12 {
+        let current = wings(&cube);
+        let moves = SETUPS
+            .iter()
+            .find_map(|setups| {
+                setups
+                    .iter()
+                    .flat_map(|setup| PAIRING.iter().map(move |pairing| (setup, pairing)))
+                    .map(|((setup, s), (pairing, p))| {
+                        let count = paired_edges(&compose(&compose(&current, s), p));
+                        (setup, pairing, count)
+                    })
+                    .filter(|(_, _, count)| *count > paired)
+                    .min_by_key(|(setup, pairing, count)| (setup.len() + pairing.len(), 12 - count))
+                    .map(|(setup, pairing, _)| [&setup[..], &pairing[..]].concat())
+            })
+            .ok_or_else(|| anyhow!("no algorithm pairs more than {paired} edges"))?;
+
+        cube.apply_moves(&moves);
+        paired = paired_edges(&wings(&cube));
+        result.extend(moves);
+    }
+
+    Ok(result)
+}
+
+/// Names of the steps of the reduction method.
+pub(super) const STEPS: [&str; 5] = ["centers", "edges", "OLL parity", "PLL parity", "3x3x3"];
+
+/// Moves of a step of the reduction method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReductionStep {
+    pub name: &'static str,
+    pub moves: Vec<BigMove>,
+}
+
+/// Solution of the 4x4x4 cube by the reduction method: solving the centers,
+/// pairing the edges, fixing the parities which the 3x3x3 cube cannot have
+/// and solving the cube as a 3x3x3 cube. Parity steps are only included
+/// when needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReductionSolution {
+    pub steps: Vec<ReductionStep>,
+}
+
+impl ReductionSolution {
+    /// Returns the moves of all steps.
+    pub fn moves(&self) -> Vec<BigMove> {
+        self.steps
+            .iter()
+            .flat_map(|step| step.moves.clone())
+            .collect()
+    }
+}
+
+impl Display for ReductionSolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            let moves = step
+                .moves
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}: {}", step.name, moves)?;
+        }
+        Ok(())
+    }
+}
+
+/// Solves the 4x4x4 cube by the reduction method with moves in WCA
+/// notation, reducing it to a 3x3x3 cube solved by
+/// [`solve_thistlethwaite`].
+///
+/// The distance tables of the centers are generated on first use.
+pub fn solve_reduction(cube: &BigCube<4>) -> Result<ReductionSolution> {
+    let mut cube = cube.clone();
+    let mut steps = vec![];
+
+    let mut centers = vec![];
+    for stage in [&*CENTERS1, &*CENTERS2, &*CENTERS3] {
+        let moves = stage
+            .solve(&cube)
+            .ok_or_else(|| anyhow!("the centers cannot be solved"))?;
+        cube.apply_moves(&moves);
+        centers.extend(moves);
+    }
+    steps.push((STEPS[0], centers));
+
+    let edges = pair_edges(&cube)?;
+    cube.apply_moves(&edges);
+    steps.push((STEPS[1], edges));
+
+    if cube.has_oll_parity() {
+        let moves = BigMove::parse_sequence(OLL_PARITY)?;
+        cube.apply_moves(&moves);
+        steps.push((STEPS[2], moves));
+    }
+    if cube.has_pll_parity() == Some(true) {
+        let moves = BigMove::parse_sequence(PLL_PARITY)?;
+        cube.apply_moves(&moves);
+        steps.push((STEPS[3], moves));
+    }
+
+    let edges = cube
+        .paired_edges()
+        .ok_or_else(|| anyhow!("the edges are not paired"))?;
+    let rotation = Rotation::from_pieces(edges, cube.corners().clone());
+    rotation.check_solvable()?;
+    let solution = solve_thistlethwaite(&Cube { rotation })?;
+    steps.push((
+        STEPS[4],
+        solution
+            .operations()
+            .into_iter()
+            .map(BigMove::from)
+            .collect(),
+    ));
+
+    Ok(ReductionSolution {
+        steps: steps
+            .into_iter()
+            .map(|(name, moves)| ReductionStep { name, moves })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sizes() {
+        assert_eq!(CENTERS1.table.len(), 735471);
+        assert_eq!(CENTERS2.table.len(), 900900);
+        assert_eq!(CENTERS3.table.len(), 343000);
+    }
+
+    #[test]
+    fn test_parity_algorithms() -> anyhow::Result<()> {
+        let (_, oll) = algorithm(BigMove::parse_sequence(OLL_PARITY)?);
+        assert!(oll.are_centers_solved());
+        assert!(oll.has_oll_parity());
+        assert_eq!(oll.has_pll_parity(), Some(false));
+
+        let (_, pll) = algorithm(BigMove::parse_sequence(PLL_PARITY)?);
+        assert!(pll.are_centers_solved());
+        assert!(!pll.has_oll_parity());
+        assert_eq!(pll.has_pll_parity(), Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_reduction() -> anyhow::Result<()> {
+        let scrambles = [
+            (
+                "R' D2 F2 Lw' Dw2 D2 R Lw Dw B2 Rw Lw B2 Uw' R F D Lw Bw2 Lw Bw Fw' D L2 F' \
+                 D D Dw' Lw Bw'",
+                vec!["OLL parity"],
+            ),
+            (
+                "F Lw' Uw L2 Bw' U2 Fw Lw' L2 Lw' Dw' F2 Bw2 F' Rw' L U2 U2 U' Bw L D2 F' D2 \
+                 L' B' R2 D Dw2 B",
+                vec!["PLL parity"],
+            ),
+            (
+                "U2 Lw' Uw' Lw Rw Fw' L L B2 L2 Uw2 Dw' L Fw' Lw Bw R' Rw' Uw2 F' Bw Uw Rw R2 \
+                 L2 Uw2 L' Bw Lw' U'",
+                vec!["OLL parity", "PLL parity"],
+            ),
+        ];
+
+        for (scramble, parities) in scrambles {
+            let mut cube = Cube4::new();
+            cube.apply_moves(&BigMove::parse_sequence(scramble)?);
+            let solution = solve_reduction(&cube)?;
+            cube.apply_moves(&solution.moves());
+
+            assert!(cube.is_solved(), "{solution}");
+            assert_eq!(solution.steps[0].name, "centers");
+            assert_eq!(solution.steps.last().unwrap().name, "3x3x3");
+            let names = solution.steps.iter().map(|step| step.name);
+            assert_eq!(
+                names
+                    .filter(|name| name.contains("parity"))
+                    .collect::<Vec<_>>(),
+                parities
+            );
+        }
+
+        Ok(())
+    }
+}
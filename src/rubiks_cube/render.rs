@@ -0,0 +1,217 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use super::{Cube, Face, Facelets};
+
+/// Colors of the stickers of each face as RGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorScheme {
+    colors: [[u8; 3]; 6],
+}
+
+impl ColorScheme {
+    /// Builds the scheme from the colors of the faces in the order of
+    /// [`Face::all`].
+    pub fn new(colors: [[u8; 3]; 6]) -> Self {
+        Self { colors }
+    }
+
+    pub fn color(&self, face: Face) -> [u8; 3] {
+        self.colors[face as usize]
+    }
+
+    pub fn with_color(mut self, face: Face, color: [u8; 3]) -> Self {
+        self.colors[face as usize] = color;
+        self
+    }
+}
+
+impl Default for ColorScheme {
+    /// The Western scheme: white U, red R, green F, yellow D, orange L and
+    /// blue B.
+    fn default() -> Self {
+        Self::new([
+            [255, 255, 255],
+            [200, 16, 46],
+            [0, 154, 68],
+            [255, 213, 0],
+            [255, 88, 0],
+            [0, 70, 173],
+        ])
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = anyhow::Error;
+
+    /// Parses the colors of the faces in the order of [`Face::all`] as hex
+    /// colors separated by commas, e.g. `#ffffff,#c8102e,...`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let colors = s
+            .split(',')
+            .map(|color| {
+                let hex = color.trim().trim_start_matches('#');
+                if hex.len() != 6 || !hex.is_ascii() {
+                    return Err(anyhow!("invalid color: {color}"));
+                }
+                let channel = |i: usize| {
+                    u8::from_str_radix(&hex[i..i + 2], 16)
+                        .map_err(|_| anyhow!("invalid color: {color}"))
+                };
+                Ok([channel(0)?, channel(2)?, channel(4)?])
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let colors: [[u8; 3]; 6] = colors
+            .try_into()
+            .map_err(|colors: Vec<_>| anyhow!("expected 6 colors, got {}", colors.len()))?;
+
+        Ok(Self::new(colors))
+    }
+}
+
+/// Unfolded net of the cube as text, U above L, F, R and B above D, with the
+/// letter of the color of each sticker:
+///
+/// ```text
+///       U U U
+///       U U U
+///       U U U
+/// L L L F F F R R R B B B
+/// L L L F F F R R R B B B
+/// L L L F F F R R R B B B
+///       D D D
+///       D D D
+///       D D D
+/// ```
+///
+/// With a color scheme, the stickers are drawn on their colors with ANSI
+/// 24-bit escapes for terminals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Net {
+    facelets: Facelets,
+    colors: Option<ColorScheme>,
+}
+
+impl Net {
+    pub fn new(facelets: Facelets) -> Self {
+        Self {
+            facelets,
+            colors: None,
+        }
+    }
+
+    pub fn with_colors(mut self, colors: ColorScheme) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    fn sticker(&self, color: Face) -> String {
+        match &self.colors {
+            Some(scheme) => {
+                let [r, g, b] = scheme.color(color);
+                // black or white letters, whichever is readable on the color
+                let luma = 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+                let fg = if luma > 128_000 { 30 } else { 97 };
+                format!("\x1b[{fg};48;2;{r};{g};{b}m {color} \x1b[0m")
+            }
+            None => color.to_string(),
+        }
+    }
+}
+
+impl Display for Net {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (separator, indent) = match self.colors {
+            Some(_) => ("", " ".repeat(9)),
+            None => (" ", " ".repeat(6)),
+        };
+
+        for faces in [
+            &[Face::U][..],
+            &[Face::L, Face::F, Face::R, Face::B],
+            &[Face::D],
+        ] {
+            for row in 0..3 {
+                let stickers = faces
+                    .iter()
+                    .flat_map(|face| &self.facelets.face(*face)[row * 3..row * 3 + 3])
+                    .map(|color| self.sticker(*color))
+                    .collect::<Vec<_>>();
+                if faces.len() == 1 {
+                    f.write_str(&indent)?;
+                }
+                writeln!(f, "{}", stickers.join(separator))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Cube {
+    /// Returns the unfolded net of the cube, which can be colored with
+    /// [`Net::with_colors`].
+    pub fn net(&self) -> Net {
+        Net::new(Facelets::from_rotation(&self.rotation))
+    }
+}
+
+impl Display for Cube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.net())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operation;
+
+    #[test]
+    fn test_net() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        cube.apply_operation(&"R".parse::<Operation>()?);
+
+        let expected = [
+            "      U U F",
+            "      U U F",
+            "      U U F",
+            "L L L F F D R R R U B B",
+            "L L L F F D R R R U B B",
+            "L L L F F D R R R U B B",
+            "      D D B",
+            "      D D B",
+            "      D D B",
+        ];
+        assert_eq!(cube.to_string().lines().collect::<Vec<_>>(), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_colored_net() {
+        let net = Cube::new().net().with_colors(ColorScheme::default());
+        let text = net.to_string();
+
+        assert_eq!(text.lines().count(), 9);
+        assert_eq!(text.matches("\x1b[0m").count(), 54);
+        assert!(text.contains("\x1b[30;48;2;255;255;255m U \x1b[0m"));
+        assert!(text.contains("\x1b[97;48;2;0;70;173m B \x1b[0m"));
+    }
+
+    #[test]
+    fn test_parse_color_scheme() -> anyhow::Result<()> {
+        let scheme: ColorScheme = "#ffffff,#c8102e,#009a44,#ffd500,#ff5800,#0046ad".parse()?;
+
+        assert_eq!(scheme, ColorScheme::default());
+        assert!("#ffffff,#c8102e".parse::<ColorScheme>().is_err());
+        assert!(
+            "#fffff,#c8102e,#009a44,#ffd500,#ff5800,#0046ad"
+                .parse::<ColorScheme>()
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::{Heuristic, Keyed, Searchable};
+
+/// Entry of the open list, ordered so that the node with the smallest
+/// estimated total cost is popped first from the max-heap.
+struct Entry<T> {
+    cost: usize,
+    depth: usize,
+    node: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost && self.depth == other.depth
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // prefer deeper nodes on ties since they are closer to goals
+        other
+            .cost
+            .cmp(&self.cost)
+            .then(self.depth.cmp(&other.depth))
+    }
+}
+
+/// Searches the goal nearest to the root expanding the node with the
+/// smallest `depth + estimate` first.
+///
+/// Nodes whose estimated total cost exceeds `max_depth` are not expanded.
+pub fn a_star_search<T: Searchable + Keyed + Heuristic>(root: T, max_depth: usize) -> Option<T> {
+    let mut best_depth = HashMap::from([(root.key(), 0)]);
+    let mut open = BinaryHeap::from([Entry {
+        cost: root.estimate(),
+        depth: 0,
+        node: root,
+    }]);
+
+    while let Some(Entry { cost, depth, node }) = open.pop() {
+        if cost > max_depth {
+            break;
+        }
+        if node.is_goal() {
+            return Some(node);
+        }
+        if best_depth.get(&node.key()).is_some_and(|d| *d < depth) {
+            continue;
+        }
+
+        let Some(children) = node.next() else {
+            continue;
+        };
+        for n in children {
+            let key = n.key();
+            if best_depth.get(&key).is_some_and(|d| *d <= depth + 1) {
+                continue;
+            }
+            best_depth.insert(key, depth + 1);
+            open.push(Entry {
+                cost: depth + 1 + n.estimate(),
+                depth: depth + 1,
+                node: n,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points on a grid moved by unit steps, searching for (3, 2).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Point(i32, i32);
+
+    impl Searchable for Point {
+        fn is_goal(&self) -> bool {
+            *self == Point(3, 2)
+        }
+
+        fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
+            let Point(x, y) = *self;
+            Some([
+                Point(x + 1, y),
+                Point(x - 1, y),
+                Point(x, y + 1),
+                Point(x, y - 1),
+            ])
+        }
+    }
+
+    impl Keyed for Point {
+        type Key = (i32, i32);
+
+        fn key(&self) -> Self::Key {
+            (self.0, self.1)
+        }
+    }
+
+    impl Heuristic for Point {
+        fn estimate(&self) -> usize {
+            ((3 - self.0).abs() + (2 - self.1).abs()) as usize
+        }
+    }
+
+    #[test]
+    fn test_a_star_search() {
+        assert_eq!(a_star_search(Point(0, 0), 5), Some(Point(3, 2)));
+        assert_eq!(a_star_search(Point(0, 0), 4), None);
+    }
+
+    #[test]
+    fn test_a_star_search_root() {
+        assert_eq!(a_star_search(Point(3, 2), 0), Some(Point(3, 2)));
+    }
+}
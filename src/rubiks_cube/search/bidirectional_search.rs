@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::Invertible;
+
+/// Visited states with their depths and the parent states and moves
+type Visited<K, M> = HashMap<K, (usize, Option<(K, M)>)>;
+
+/// Expands all nodes in the frontier by one step and returns the next
+/// frontier with the shortest total depth and key of the meeting nodes.
+fn expand<T: Invertible>(
+    frontier: Vec<T>,
+    depth: usize,
+    visited: &mut Visited<T::Key, T::Move>,
+    other: &Visited<T::Key, T::Move>,
+) -> (Vec<T>, Option<(usize, T::Key)>) {
+    let mut next_frontier = vec![];
+    let mut meeting: Option<(usize, T::Key)> = None;
+
+    for node in frontier {
+        let key = node.key();
+        for (m, n) in node.transitions() {
+            let next_key = n.key();
+            if visited.contains_key(&next_key) {
+                continue;
+            }
+            visited.insert(next_key.clone(), (depth + 1, Some((key.clone(), m))));
+
+            if let Some((other_depth, _)) = other.get(&next_key) {
+                let total = depth + 1 + other_depth;
+                if meeting.as_ref().is_none_or(|(d, _)| total < *d) {
+                    meeting = Some((total, next_key));
+                }
+                continue;
+            }
+            next_frontier.push(n);
+        }
+    }
+
+    (next_frontier, meeting)
+}
+
+/// Returns the moves from the root of the visited tree to the node of `key`.
+fn moves_to<K: Hash + Eq, M: Clone>(visited: &Visited<K, M>, key: &K) -> Vec<M> {
+    let mut result = vec![];
+    let mut current = key;
+    while let Some((_, Some((parent, m)))) = visited.get(current) {
+        result.push(m.clone());
+        current = parent;
+    }
+
+    result.into_iter().rev().collect()
+}
+
+/// Searches the shortest moves from `start` to `goal` by breadth-first
+/// search from both sides, expanding the smaller frontier first, until they
+/// meet in the middle.
+pub fn bidirectional_search<T: Invertible>(
+    start: T,
+    goal: T,
+    max_depth: usize,
+) -> Option<Vec<T::Move>> {
+    if start.key() == goal.key() {
+        return Some(vec![]);
+    }
+
+    let mut forward: Visited<T::Key, T::Move> = HashMap::from([(start.key(), (0, None))]);
+    let mut backward: Visited<T::Key, T::Move> = HashMap::from([(goal.key(), (0, None))]);
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![goal];
+    let mut forward_depth = 0;
+    let mut backward_depth = 0;
+
+    while forward_depth + backward_depth < max_depth {
+        let meeting = if forward_frontier.len() <= backward_frontier.len() {
+            let (frontier, meeting) =
+                expand(forward_frontier, forward_depth, &mut forward, &backward);
+            forward_frontier = frontier;
+            forward_depth += 1;
+            meeting
+        } else {
+            let (frontier, meeting) =
+                expand(backward_frontier, backward_depth, &mut backward, &forward);
+            backward_frontier = frontier;
+            backward_depth += 1;
+            meeting
+        };
+
+        if let Some((_, key)) = meeting {
+            let mut result = moves_to(&forward, &key);
+            let backward_moves = moves_to(&backward, &key);
+            result.extend(backward_moves.iter().rev().map(T::inverse));
+            return Some(result);
+        }
+        if forward_frontier.is_empty() || backward_frontier.is_empty() {
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Keyed, Searchable};
+    use super::*;
+
+    /// Integers moved by adding ±1 or ±5.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Number(i32);
+
+    impl Searchable for Number {
+        fn is_goal(&self) -> bool {
+            self.0 == 0
+        }
+
+        fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
+            Some(self.transitions().into_iter().map(|(_, n)| n))
+        }
+    }
+
+    impl Keyed for Number {
+        type Key = i32;
+
+        fn key(&self) -> Self::Key {
+            self.0
+        }
+    }
+
+    impl Invertible for Number {
+        type Move = i32;
+
+        fn transitions(&self) -> impl IntoIterator<Item = (Self::Move, Self)> {
+            [1, -1, 5, -5].map(|m| (m, Number(self.0 + m)))
+        }
+
+        fn inverse(m: &Self::Move) -> Self::Move {
+            -m
+        }
+    }
+
+    #[test]
+    fn test_bidirectional_search() {
+        let result = bidirectional_search(Number(12), Number(0), 10).unwrap();
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(12 + result.iter().sum::<i32>(), 0);
+    }
+
+    #[test]
+    fn test_bidirectional_search_odd_length() {
+        let result = bidirectional_search(Number(-11), Number(0), 10).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(-11 + result.iter().sum::<i32>(), 0);
+    }
+
+    #[test]
+    fn test_bidirectional_search_max_depth() {
+        assert_eq!(bidirectional_search(Number(12), Number(0), 3), None);
+    }
+
+    #[test]
+    fn test_bidirectional_search_root() {
+        assert_eq!(bidirectional_search(Number(3), Number(3), 0), Some(vec![]));
+    }
+}
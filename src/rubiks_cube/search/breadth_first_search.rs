@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use super::{Keyed, Searchable};
+
+/// Searches the goal nearest to the root level by level, skipping states
+/// already visited.
+///
+/// Keeps every visited key in memory, so it is only suitable for shallow
+/// goals.
+pub fn breadth_first_search<T: Searchable + Keyed>(root: T, max_depth: usize) -> Option<T> {
+    if root.is_goal() {
+        return Some(root);
+    }
+
+    let mut visited = HashSet::from([root.key()]);
+    let mut frontier = vec![root];
+
+    for _ in 0..max_depth {
+        let mut next_frontier = vec![];
+        for node in frontier {
+            let Some(children) = node.next() else {
+                continue;
+            };
+            for n in children {
+                if !visited.insert(n.key()) {
+                    continue;
+                }
+                if n.is_goal() {
+                    return Some(n);
+                }
+                next_frontier.push(n);
+            }
+        }
+
+        if next_frontier.is_empty() {
+            break;
+        }
+        frontier = next_frontier;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Integers moved by doubling or adding 1, searching for 10.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Number(u32);
+
+    impl Searchable for Number {
+        fn is_goal(&self) -> bool {
+            self.0 == 10
+        }
+
+        fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
+            if self.0 > 10 {
+                return None;
+            }
+            Some([Number(self.0 * 2), Number(self.0 + 1)])
+        }
+    }
+
+    impl Keyed for Number {
+        type Key = u32;
+
+        fn key(&self) -> Self::Key {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_breadth_first_search() {
+        assert_eq!(breadth_first_search(Number(1), 4), Some(Number(10)));
+        assert_eq!(breadth_first_search(Number(1), 3), None);
+    }
+
+    #[test]
+    fn test_breadth_first_search_root() {
+        assert_eq!(breadth_first_search(Number(10), 0), Some(Number(10)));
+    }
+
+    #[test]
+    fn test_breadth_first_search_exhausted() {
+        assert_eq!(breadth_first_search(Number(11), 10), None);
+    }
+}
@@ -14,7 +14,7 @@ fn depth_limited_search_internal<T: Searchable + Prunable>(
         if n.is_goal() {
             return Some(n);
         }
-        if n.to_prune(depth, max_depth) {
+        if n.to_prune(depth + 1, max_depth) {
             continue;
         }
         if let Some(result) = depth_limited_search_internal(n, depth + 1, max_depth) {
@@ -0,0 +1,7 @@
+/// A node which can estimate the number of steps remaining to a goal.
+///
+/// The estimate must never exceed the actual number of steps for searches
+/// to find the shortest path.
+pub trait Heuristic {
+    fn estimate(&self) -> usize;
+}
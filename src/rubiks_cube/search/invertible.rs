@@ -0,0 +1,14 @@
+use super::{Keyed, Searchable};
+
+/// A searchable node whose transitions are labelled by moves, each of which
+/// can be undone by its inverse move.
+///
+/// The set of moves must be closed under inversion, so that the
+/// predecessors of a node are reached by its transitions as well.
+pub trait Invertible: Searchable + Keyed {
+    type Move: Clone;
+
+    fn transitions(&self) -> impl IntoIterator<Item = (Self::Move, Self)>;
+
+    fn inverse(m: &Self::Move) -> Self::Move;
+}
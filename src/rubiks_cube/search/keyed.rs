@@ -0,0 +1,9 @@
+use std::hash::Hash;
+
+/// A node whose state can be identified by a key, so that searches can
+/// detect states already visited.
+pub trait Keyed {
+    type Key: Hash + Eq + Clone;
+
+    fn key(&self) -> Self::Key;
+}
@@ -1,10 +1,83 @@
+//! Generic search algorithms over puzzles and other state spaces.
+//!
+//! A problem is described by implementing [`State`] for its states, which
+//! gives the goal test and the moves available from each state. Wrapping
+//! the initial state in [`SearchNode::root`] gives a [`Searchable`] node
+//! tracking the path of moves, which can be passed to the search functions:
+//!
+//! - [`iterative_deepening_dfs`] and [`depth_limited_search`] require
+//!   [`Prunable`] to cut off subtrees which cannot reach a goal in time.
+//!   Their `_with_table` variants also require [`Keyed`] and skip states
+//!   reached again through a [`TranspositionTable`].
+//! - [`breadth_first_search`] requires [`Keyed`] to skip visited states.
+//! - [`a_star_search`] requires [`Keyed`] and [`Heuristic`].
+//! - [`bidirectional_search`] works on nodes implementing [`Invertible`]
+//!   and searches from both the start and the goal.
+//!
+//! [`MoveAutomaton`] generates the canonical sequences of moves from their
+//! commutation relations, so that states can avoid offering redundant moves.
+//!
+//! ```
+//! use rubiks_cube::search::{self, Keyed, SearchNode, State};
+//!
+//! /// Reach 10 from 1 by doubling or adding 1.
+//! #[derive(Debug, Clone)]
+//! struct Number(u32);
+//!
+//! impl State for Number {
+//!     type Move = &'static str;
+//!
+//!     fn is_goal(&self) -> bool {
+//!         self.0 == 10
+//!     }
+//!
+//!     fn moves(&self, _last: Option<&Self::Move>) -> impl IntoIterator<Item = (Self::Move, Self)> {
+//!         [("double", Number(self.0 * 2)), ("increment", Number(self.0 + 1))]
+//!     }
+//! }
+//!
+//! impl Keyed for Number {
+//!     type Key = u32;
+//!
+//!     fn key(&self) -> u32 {
+//!         self.0
+//!     }
+//! }
+//!
+//! let result = search::breadth_first_search(SearchNode::root(Number(1)), 5).unwrap();
+//! assert_eq!(result.path(), vec!["double", "double", "increment", "double"]);
+//! ```
+//!
+//! See `examples/fifteen_puzzle.rs` for a larger problem.
+
+mod a_star_search;
+mod bidirectional_search;
+mod breadth_first_search;
 mod depth_limited_search;
+mod heuristic;
+mod invertible;
 mod iterative_deepening_dfs;
+mod keyed;
+mod move_automaton;
 mod prunable;
+mod search_node;
 mod searchable;
+mod transposition_table;
 mod tree;
 
+pub use a_star_search::a_star_search;
+pub use bidirectional_search::bidirectional_search;
+pub use breadth_first_search::breadth_first_search;
 pub use depth_limited_search::depth_limited_search;
+pub use heuristic::Heuristic;
+pub use invertible::Invertible;
 pub use iterative_deepening_dfs::iterative_deepening_dfs;
+pub use keyed::Keyed;
+pub use move_automaton::MoveAutomaton;
 pub use prunable::Prunable;
+pub use search_node::{SearchNode, State};
 pub use searchable::Searchable;
+pub use transposition_table::{
+    TranspositionTable, depth_limited_search_with_table, iterative_deepening_dfs_with_table,
+};
+pub use tree::Tree;
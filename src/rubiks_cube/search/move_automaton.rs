@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+/// Finite-state automaton accepting exactly one sequence of moves among
+/// those equal by commuting moves, and rejecting the sequences where a move
+/// could be commuted next to another move to merge with it.
+///
+/// Moves are given by indices, ordered by them. Of the sequences equal by
+/// commuting adjacent moves, the lexicographically smallest is accepted, so
+/// e.g. for the cube only `R L` is accepted of `R L` and `L R`, and `R L R`
+/// is rejected as `R2 L`.
+///
+/// Each state is the set of moves which may not be played next: the moves
+/// smaller than or merging with an earlier move which they commute with
+/// along with all moves after it.
+#[derive(Debug, Clone)]
+pub struct MoveAutomaton {
+    num_moves: usize,
+    transitions: Vec<Option<usize>>,
+}
+
+impl MoveAutomaton {
+    /// State before any move.
+    pub const START: usize = 0;
+
+    /// Builds the automaton of `num_moves` moves (at most 64) from the
+    /// relations between them: `commute(a, b)` if playing `a` then `b` is
+    /// the same as `b` then `a`, and `merge(a, b)` if playing `a` then `b`
+    /// is the same as playing a single move or none.
+    pub fn new(
+        num_moves: usize,
+        commute: impl Fn(usize, usize) -> bool,
+        merge: impl Fn(usize, usize) -> bool,
+    ) -> Self {
+        assert!(num_moves <= 64, "too many moves: {num_moves}");
+
+        let masks = (0..num_moves)
+            .map(|a| {
+                let commuting = (0..num_moves)
+                    .filter(|x| commute(*x, a))
+                    .fold(0u64, |acc, x| acc | 1 << x);
+                let forbidden = (0..num_moves)
+                    .filter(|x| commute(*x, a) && (*x < a || merge(*x, a)))
+                    .fold(0u64, |acc, x| acc | 1 << x);
+                (commuting, forbidden)
+            })
+            .collect::<Vec<_>>();
+
+        let mut states = vec![0u64];
+        let mut indices = HashMap::from([(0u64, Self::START)]);
+        let mut transitions = vec![];
+        let mut i = 0;
+        while i < states.len() {
+            let state = states[i];
+            for (a, (commuting, forbidden)) in masks.iter().enumerate() {
+                if state >> a & 1 == 1 {
+                    transitions.push(None);
+                    continue;
+                }
+
+                let next = state & commuting | forbidden;
+                let index = *indices.entry(next).or_insert_with(|| {
+                    states.push(next);
+                    states.len() - 1
+                });
+                transitions.push(Some(index));
+            }
+            i += 1;
+        }
+
+        Self {
+            num_moves,
+            transitions,
+        }
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.transitions.len() / self.num_moves.max(1)
+    }
+
+    /// Returns the state after playing the move in the state, or `None` if
+    /// the move may not be played.
+    pub fn next(&self, state: usize, m: usize) -> Option<usize> {
+        self.transitions[state * self.num_moves + m]
+    }
+
+    /// Returns the moves which may be played in the state with the states
+    /// after them.
+    pub fn moves(&self, state: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.num_moves).filter_map(move |m| Some((m, self.next(state, m)?)))
+    }
+
+    /// Returns true if the automaton accepts the sequence of moves.
+    pub fn accepts(&self, moves: &[usize]) -> bool {
+        moves
+            .iter()
+            .try_fold(Self::START, |state, m| self.next(state, *m))
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the accepted sequences of each length up to `max_length`.
+    fn counts(automaton: &MoveAutomaton, max_length: usize) -> Vec<usize> {
+        let mut result = vec![];
+        let mut states = vec![MoveAutomaton::START];
+        for _ in 0..=max_length {
+            result.push(states.len());
+            states = states
+                .iter()
+                .flat_map(|s| automaton.moves(*s).map(|(_, next)| next))
+                .collect();
+        }
+        result
+    }
+
+    #[test]
+    fn test_commuting_moves() {
+        // three commuting moves of order 2, such as half turns of slices
+        // on the same axis
+        let automaton = MoveAutomaton::new(3, |_, _| true, |a, b| a == b);
+
+        assert_eq!(counts(&automaton, 4), vec![1, 3, 3, 1, 0]);
+        assert!(automaton.accepts(&[0, 2]));
+        assert!(!automaton.accepts(&[2, 0]));
+        assert!(!automaton.accepts(&[0, 2, 0]));
+    }
+
+    #[test]
+    fn test_free_moves() {
+        // two moves of infinite order without relations
+        let automaton = MoveAutomaton::new(2, |a, b| a == b, |_, _| false);
+
+        assert_eq!(counts(&automaton, 3), vec![1, 2, 4, 8]);
+        assert_eq!(automaton.num_states(), 1);
+    }
+
+    #[test]
+    fn test_partially_commuting_moves() {
+        // 0 and 2 commute with 1 but not with each other
+        let automaton = MoveAutomaton::new(3, |a, b| a == b || a == 1 || b == 1, |a, b| a == b);
+
+        assert!(automaton.accepts(&[0, 1, 2, 0]));
+        assert!(automaton.accepts(&[0, 2, 0]));
+        assert!(!automaton.accepts(&[1, 0]));
+        assert!(!automaton.accepts(&[0, 1, 0]));
+        assert!(!automaton.accepts(&[0, 2, 1]));
+    }
+}
@@ -1,3 +1,5 @@
 pub trait Prunable {
+    /// Returns true if the subtree under the node at `depth` cannot contain
+    /// a goal within `max_depth`.
     fn to_prune(&self, depth: usize, max_depth: usize) -> bool;
 }
@@ -1,7 +1,174 @@
-use super::Searchable;
+use std::fmt::Debug;
+use std::rc::Rc;
 
-#[derive (Clone, Debug)]
-pub struct SearchNode<T> where T: Searchable {
-    item: T,
+use super::{Heuristic, Keyed, Prunable, Searchable};
+
+/// A state of a problem whose transitions are labelled by moves.
+pub trait State: Clone {
+    type Move: Clone;
+
+    fn is_goal(&self) -> bool;
+
+    /// Returns the moves available from the state with the states they lead
+    /// to. `last` is the move which led to this state, which allows
+    /// skipping redundant sequences such as a move followed by its inverse.
+    fn moves(&self, last: Option<&Self::Move>) -> impl IntoIterator<Item = (Self::Move, Self)>;
+}
+
+struct Inner<T: State> {
+    state: T,
     depth: usize,
+    parent: Option<(SearchNode<T>, T::Move)>,
+}
+
+/// A searchable node which wraps a [`State`] and tracks the path of moves
+/// from the root.
+///
+/// Nodes share their ancestors, so cloning a node is cheap.
+pub struct SearchNode<T: State> {
+    inner: Rc<Inner<T>>,
+}
+
+impl<T: State> SearchNode<T> {
+    /// Returns the root node of the search from `state`.
+    pub fn root(state: T) -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                state,
+                depth: 0,
+                parent: None,
+            }),
+        }
+    }
+
+    pub fn state(&self) -> &T {
+        &self.inner.state
+    }
+
+    pub fn depth(&self) -> usize {
+        self.inner.depth
+    }
+
+    /// Returns the move which led to this node, if not the root.
+    pub fn last_move(&self) -> Option<&T::Move> {
+        self.inner.parent.as_ref().map(|(_, m)| m)
+    }
+
+    /// Returns the moves from the root to this node.
+    pub fn path(&self) -> Vec<T::Move> {
+        let mut result = vec![];
+        let mut current = self;
+        while let Some((parent, m)) = &current.inner.parent {
+            result.push(m.clone());
+            current = parent;
+        }
+
+        result.into_iter().rev().collect()
+    }
+}
+
+impl<T: State> Clone for SearchNode<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: State + Debug> Debug for SearchNode<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchNode")
+            .field("state", &self.inner.state)
+            .field("depth", &self.inner.depth)
+            .finish()
+    }
+}
+
+impl<T: State> Searchable for SearchNode<T> {
+    fn is_goal(&self) -> bool {
+        self.inner.state.is_goal()
+    }
+
+    fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
+        let nodes = self
+            .inner
+            .state
+            .moves(self.last_move())
+            .into_iter()
+            .map(|(m, state)| Self {
+                inner: Rc::new(Inner {
+                    state,
+                    depth: self.inner.depth + 1,
+                    parent: Some((self.clone(), m)),
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        Some(nodes)
+    }
+}
+
+impl<T: State + Keyed> Keyed for SearchNode<T> {
+    type Key = T::Key;
+
+    fn key(&self) -> Self::Key {
+        self.inner.state.key()
+    }
+}
+
+impl<T: State + Heuristic> Heuristic for SearchNode<T> {
+    fn estimate(&self) -> usize {
+        self.inner.state.estimate()
+    }
+}
+
+impl<T: State + Prunable> Prunable for SearchNode<T> {
+    fn to_prune(&self, depth: usize, max_depth: usize) -> bool {
+        self.inner.state.to_prune(depth, max_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::iterative_deepening_dfs;
+    use super::*;
+
+    /// Lights in a row toggled with their neighbors, searching for all on.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Lights([bool; 5]);
+
+    impl State for Lights {
+        type Move = usize;
+
+        fn is_goal(&self) -> bool {
+            self.0.iter().all(|x| *x)
+        }
+
+        fn moves(&self, last: Option<&usize>) -> impl IntoIterator<Item = (usize, Self)> {
+            (0..5).filter(move |i| last != Some(i)).map(|i| {
+                let mut lights = self.0;
+                for light in &mut lights[i.saturating_sub(1)..=(i + 1).min(4)] {
+                    *light = !*light;
+                }
+                (i, Lights(lights))
+            })
+        }
+    }
+
+    impl Prunable for Lights {
+        fn to_prune(&self, _depth: usize, _max_depth: usize) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_path() {
+        let root = SearchNode::root(Lights([false, false, true, false, false]));
+        let result = iterative_deepening_dfs(root, 3).unwrap();
+
+        assert_eq!(result.path(), vec![0, 4]);
+        assert_eq!(result.depth(), 2);
+        assert_eq!(result.last_move(), Some(&4));
+        assert!(result.state().is_goal());
+    }
 }
@@ -1,4 +1,6 @@
+/// A node of a search tree.
 pub trait Searchable: Sized + Clone {
+    /// Returns the children of the node, or `None` if it is a leaf.
     fn next(&self) -> Option<impl IntoIterator<Item = Self>>;
 
     fn is_goal(&self) -> bool;
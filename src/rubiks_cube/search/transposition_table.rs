@@ -0,0 +1,249 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use super::{Keyed, Prunable, Searchable};
+
+/// Bounded table of the states visited by a depth-first search, with the
+/// number of moves which were left to reach a goal from them.
+///
+/// States are stored with their keys in the slot given by the hash of the
+/// key. Newer states replace older ones in their slot, so the table never
+/// grows but may forget states, which only makes the search slower.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable<K> {
+    entries: Vec<Option<(K, usize)>>,
+}
+
+impl<K: Hash + Eq + Clone> TranspositionTable<K> {
+    /// Returns an empty table holding up to `capacity` states.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: vec![None; capacity.max(1)],
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    /// Records the node with `remaining` moves left and returns true if it
+    /// was already visited with at least as many moves left, i.e. at equal
+    /// or smaller depth for the same maximum depth.
+    fn visit<T: Keyed<Key = K>>(&mut self, node: &T, remaining: usize) -> bool {
+        let key = node.key();
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        let index = (hasher.finish() % self.entries.len() as u64) as usize;
+        if let Some((k, r)) = &self.entries[index]
+            && *k == key
+            && *r >= remaining
+        {
+            return true;
+        }
+
+        self.entries[index] = Some((key, remaining));
+        false
+    }
+}
+
+fn depth_limited_search_internal<T: Searchable + Prunable + Keyed>(
+    node: T,
+    depth: usize,
+    max_depth: usize,
+    table: &mut TranspositionTable<T::Key>,
+) -> Option<T> {
+    if depth >= max_depth {
+        return None;
+    }
+
+    for n in node.next()?.into_iter() {
+        if n.is_goal() {
+            return Some(n);
+        }
+        if n.to_prune(depth + 1, max_depth) || table.visit(&n, max_depth - depth - 1) {
+            continue;
+        }
+        if let Some(result) = depth_limited_search_internal(n, depth + 1, max_depth, table) {
+            return Some(result);
+        }
+    }
+
+    None
+}
+
+/// Same as [`depth_limited_search`](super::depth_limited_search) but skips
+/// the nodes found in `table`, which are reached again by different moves
+/// and were already searched at least as deep.
+///
+/// The children of a node must not depend on the path to it, except for
+/// skipping sequences which have equivalent ones of at most the same
+/// length, such as a move followed by its inverse.
+pub fn depth_limited_search_with_table<T: Searchable + Prunable + Keyed>(
+    root: T,
+    max_depth: usize,
+    table: &mut TranspositionTable<T::Key>,
+) -> Option<T> {
+    if root.is_goal() {
+        return Some(root);
+    }
+    if root.to_prune(0, max_depth) {
+        return None;
+    }
+
+    table.visit(&root, max_depth);
+    depth_limited_search_internal(root, 0, max_depth, table)
+}
+
+/// Same as [`iterative_deepening_dfs`](super::iterative_deepening_dfs) but
+/// skips the nodes found in `table`. The table is kept between iterations
+/// since it records how deep each node was searched.
+pub fn iterative_deepening_dfs_with_table<T: Searchable + Prunable + Keyed>(
+    root: T,
+    max_depth: usize,
+    table: &mut TranspositionTable<T::Key>,
+) -> Option<T> {
+    for depth in 0..=max_depth {
+        if let Some(result) = depth_limited_search_with_table(root.clone(), depth, table) {
+            return Some(result);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static EXPANDED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Points on a grid moved in the four directions, searching for the
+    /// goal at (3, 3).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Point(i32, i32);
+
+    impl Searchable for Point {
+        fn is_goal(&self) -> bool {
+            *self == Point(3, 3)
+        }
+
+        fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
+            EXPANDED.set(EXPANDED.get() + 1);
+            let Point(x, y) = *self;
+            Some([
+                Point(x - 1, y),
+                Point(x + 1, y),
+                Point(x, y - 1),
+                Point(x, y + 1),
+            ])
+        }
+    }
+
+    impl Prunable for Point {
+        fn to_prune(&self, _depth: usize, _max_depth: usize) -> bool {
+            false
+        }
+    }
+
+    impl Keyed for Point {
+        type Key = (i32, i32);
+
+        fn key(&self) -> Self::Key {
+            (self.0, self.1)
+        }
+    }
+
+    fn expanded(search: impl FnOnce() -> Option<Point>) -> (Option<Point>, usize) {
+        EXPANDED.set(0);
+        let result = search();
+        (result, EXPANDED.get())
+    }
+
+    #[test]
+    fn test_iterative_deepening_dfs_with_table() {
+        let (without, expanded_without) =
+            expanded(|| super::super::iterative_deepening_dfs(Point(0, 0), 6));
+        let mut table = TranspositionTable::new(1024);
+        let (with, expanded_with) =
+            expanded(|| iterative_deepening_dfs_with_table(Point(0, 0), 6, &mut table));
+
+        assert_eq!(without, Some(Point(3, 3)));
+        assert_eq!(with, Some(Point(3, 3)));
+        assert!(expanded_with < expanded_without);
+    }
+
+    #[test]
+    fn test_depth_limited_search_with_table_small() {
+        let mut table = TranspositionTable::new(1);
+
+        assert_eq!(table.capacity(), 1);
+        assert_eq!(
+            depth_limited_search_with_table(Point(0, 0), 6, &mut table),
+            Some(Point(3, 3))
+        );
+        assert_eq!(
+            depth_limited_search_with_table(Point(0, 0), 5, &mut table),
+            None
+        );
+    }
+
+    #[test]
+    fn test_visit() {
+        let mut table = TranspositionTable::new(16);
+
+        assert!(!table.visit(&Point(0, 0), 2));
+        assert!(table.visit(&Point(0, 0), 2));
+        assert!(table.visit(&Point(0, 0), 1));
+        assert!(!table.visit(&Point(0, 0), 3));
+
+        table.clear();
+        assert!(!table.visit(&Point(0, 0), 1));
+    }
+
+    #[test]
+    fn test_visit_eviction() {
+        let mut table = TranspositionTable::new(1);
+
+        assert!(!table.visit(&Point(0, 0), 2));
+        assert!(!table.visit(&Point(1, 0), 2));
+        // the first state was replaced by the second in the only slot
+        assert!(!table.visit(&Point(0, 0), 2));
+        assert!(table.visit(&Point(0, 0), 2));
+    }
+
+    /// Key whose values all have the same hash.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Colliding(i32);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, _state: &mut H) {}
+    }
+
+    struct CollidingPoint(i32);
+
+    impl Keyed for CollidingPoint {
+        type Key = Colliding;
+
+        fn key(&self) -> Self::Key {
+            Colliding(self.0)
+        }
+    }
+
+    #[test]
+    fn test_visit_collision() {
+        let mut table = TranspositionTable::new(1024);
+
+        assert!(!table.visit(&CollidingPoint(0), 2));
+        assert!(table.visit(&CollidingPoint(0), 2));
+        // another state with the same hash is not taken for the first one
+        assert!(!table.visit(&CollidingPoint(1), 1));
+        assert!(table.visit(&CollidingPoint(1), 1));
+    }
+}
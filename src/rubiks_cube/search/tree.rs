@@ -1,5 +1,14 @@
-use super::Searchable;
+use super::{Prunable, Searchable};
 
+/// An explicit tree whose leaves are goals, useful for trying out searches.
+///
+/// ```
+/// use rubiks_cube::search::{self, Tree};
+///
+/// let tree = Tree::Node(vec![Tree::Node(vec![Tree::Leaf(1)]), Tree::Leaf(2)]);
+///
+/// assert_eq!(search::iterative_deepening_dfs(tree, 2), Some(Tree::Leaf(2)));
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Tree<T> {
     Node(Vec<Tree<T>>),
@@ -21,3 +30,9 @@ impl<T: Clone> Searchable for Tree<T> {
         }
     }
 }
+
+impl<T: Clone> Prunable for Tree<T> {
+    fn to_prune(&self, _depth: usize, _max_depth: usize) -> bool {
+        false
+    }
+}
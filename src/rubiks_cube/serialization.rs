@@ -0,0 +1,226 @@
+//! Serialization with serde, enabled by the `serde` feature.
+//!
+//! Operations and moves are written in their notation, cubes as their
+//! facelet strings and rotations as the pieces at each position with their
+//! orientations. Deserializing rejects the states which cannot be reached by
+//! operations.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::algebra::rotation::Rotation;
+use super::reduction::STEPS;
+use super::thistlethwaite::SUBGROUPS;
+use super::{BigMove, Cube, Facelets, Operation, PhaseSolution, ReductionStep};
+
+/// Parses a string with `FromStr`, reporting its error as the error of the
+/// deserializer.
+fn parse<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(D::Error::custom)
+}
+
+impl Serialize for Operation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Operation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse(deserializer)
+    }
+}
+
+impl Serialize for BigMove {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigMove {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        parse(deserializer)
+    }
+}
+
+impl Serialize for Cube {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Facelets::from_rotation(&self.rotation))
+    }
+}
+
+impl<'de> Deserialize<'de> for Cube {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let facelets: Facelets = parse(deserializer)?;
+        let rotation = facelets.to_rotation().map_err(D::Error::custom)?;
+        Ok(Cube { rotation })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Rotation")]
+struct RotationParts {
+    edge_perm: [usize; 12],
+    edge_orient: [u8; 12],
+    corner_perm: [usize; 8],
+    corner_orient: [u8; 8],
+}
+
+impl Serialize for Rotation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let edges: [(usize, u8); 12] = std::array::from_fn(|i| self.edge(i));
+        let corners: [(usize, u8); 8] = std::array::from_fn(|i| self.corner(i));
+        RotationParts {
+            edge_perm: edges.map(|(piece, _)| piece),
+            edge_orient: edges.map(|(_, orient)| orient),
+            corner_perm: corners.map(|(piece, _)| piece),
+            corner_orient: corners.map(|(_, orient)| orient),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rotation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let parts = RotationParts::deserialize(deserializer)?;
+        let rotation = Rotation::try_from_parts(
+            parts.edge_perm,
+            parts.edge_orient,
+            parts.corner_perm,
+            parts.corner_orient,
+        )
+        .map_err(D::Error::custom)?;
+        rotation.check_solvable().map_err(D::Error::custom)?;
+        Ok(rotation)
+    }
+}
+
+/// Looks up one of `names` borrowed for the whole program.
+fn static_name<E: Error>(name: String, names: &[&'static str]) -> Result<&'static str, E> {
+    names
+        .iter()
+        .find(|n| **n == name)
+        .copied()
+        .ok_or_else(|| E::custom(format!("unknown name: {name}")))
+}
+
+#[derive(Deserialize)]
+struct PhaseParts {
+    subgroup: String,
+    operations: Vec<Operation>,
+}
+
+impl<'de> Deserialize<'de> for PhaseSolution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let parts = PhaseParts::deserialize(deserializer)?;
+        Ok(PhaseSolution {
+            subgroup: static_name(parts.subgroup, &SUBGROUPS)?,
+            operations: parts.operations,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct StepParts {
+    name: String,
+    moves: Vec<BigMove>,
+}
+
+impl<'de> Deserialize<'de> for ReductionStep {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let parts = StepParts::deserialize(deserializer)?;
+        Ok(ReductionStep {
+            name: static_name(parts.name, &STEPS)?,
+            moves: parts.moves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReductionSolution, ThistlethwaiteSolution};
+
+    #[test]
+    fn test_operations() -> anyhow::Result<()> {
+        let operations = Operation::parse_sequence("R U' F2")?;
+        let json = serde_json::to_string(&operations)?;
+
+        assert_eq!(json, r#"["R","U'","F2"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Operation>>(&json)?, operations);
+        assert!(serde_json::from_str::<Operation>(r#""X""#).is_err());
+
+        let moves = BigMove::parse_sequence("Rw 2U' 3Fw2")?;
+        let json = serde_json::to_string(&moves)?;
+        assert_eq!(serde_json::from_str::<Vec<BigMove>>(&json)?, moves);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cube() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        for operation in Operation::parse_sequence("R U F")? {
+            cube.apply_operation(&operation);
+        }
+        let json = serde_json::to_string(&cube)?;
+
+        assert_eq!(json.len(), 54 + 2);
+        assert_eq!(serde_json::from_str::<Cube>(&json)?, cube);
+
+        let rotation = serde_json::to_string(&cube.rotation)?;
+        assert_eq!(serde_json::from_str::<Rotation>(&rotation)?, cube.rotation);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_states() {
+        // a single twisted corner
+        let twisted = r#"{"edge_perm":[0,1,2,3,4,5,6,7,8,9,10,11],"edge_orient":[0,0,0,0,0,0,0,0,0,0,0,0],"corner_perm":[0,1,2,3,4,5,6,7],"corner_orient":[1,0,0,0,0,0,0,0]}"#;
+        assert!(serde_json::from_str::<Rotation>(twisted).is_err());
+        // a repeated corner
+        let repeated = twisted.replace("[0,1,2,3,4,5,6,7]", "[0,0,2,3,4,5,6,7]");
+        assert!(serde_json::from_str::<Rotation>(&repeated).is_err());
+
+        // two stickers swapped
+        let swapped = r#""UUUUUUUURURRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB""#;
+        assert!(serde_json::from_str::<Cube>(swapped).is_err());
+    }
+
+    #[test]
+    fn test_solutions() -> anyhow::Result<()> {
+        let solution = ThistlethwaiteSolution {
+            phases: SUBGROUPS
+                .iter()
+                .map(|subgroup| PhaseSolution {
+                    subgroup,
+                    operations: vec![Operation::R2],
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string(&solution)?;
+        assert_eq!(
+            serde_json::from_str::<ThistlethwaiteSolution>(&json)?,
+            solution
+        );
+        assert!(serde_json::from_str::<ThistlethwaiteSolution>(&json.replace("G4", "G5")).is_err());
+
+        let json = r#"{"steps":[{"name":"edges","moves":["Uw","R"]}]}"#;
+        let solution = serde_json::from_str::<ReductionSolution>(json)?;
+        assert_eq!(solution.steps[0].name, "edges");
+        assert_eq!(serde_json::to_string(&solution)?, json);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+use std::fmt::Display;
+use std::ops::Mul;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+
+use super::Face;
+use super::algebra::corners::Corners;
+use super::algebra::symmetric_group::SymmetricGroup;
+use super::enumeration::{self, Enumeration};
+use super::table_cache;
+
+/// Corners around which the Skewb turns in WCA notation: DBR, UBL, DFL and
+/// DBL. None of the turns moves the UFR corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkewbAxis {
+    R,
+    U,
+    L,
+    B,
+}
+
+impl SkewbAxis {
+    pub fn all() -> [SkewbAxis; 4] {
+        [SkewbAxis::R, SkewbAxis::U, SkewbAxis::L, SkewbAxis::B]
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Move of the Skewb: a turn of the half of the puzzle around a corner by a
+/// third, clockwise when looking at the corner, written `R` or `R'`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SkewbMove {
+    pub axis: SkewbAxis,
+    pub clockwise: bool,
+}
+
+impl SkewbMove {
+    pub fn all() -> Vec<SkewbMove> {
+        SkewbAxis::all()
+            .into_iter()
+            .flat_map(|axis| [true, false].map(|clockwise| SkewbMove { axis, clockwise }))
+            .collect()
+    }
+
+    /// Returns the move which cancels this move.
+    pub fn inverse(&self) -> SkewbMove {
+        SkewbMove {
+            clockwise: !self.clockwise,
+            ..*self
+        }
+    }
+
+    /// Parses moves separated by whitespace, e.g. `R U' L B'`.
+    pub fn parse_sequence(s: &str) -> Result<Vec<SkewbMove>> {
+        s.split_whitespace().map(str::parse).collect()
+    }
+
+    /// Index of the move in [`SkewbMove::all`].
+    fn index(&self) -> usize {
+        self.axis.index() * 2 + !self.clockwise as usize
+    }
+}
+
+impl FromStr for SkewbMove {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (letter, clockwise) = match s.strip_suffix('\'') {
+            Some(letter) => (letter, false),
+            None => (s, true),
+        };
+        let axis = match letter {
+            "R" => SkewbAxis::R,
+            "U" => SkewbAxis::U,
+            "L" => SkewbAxis::L,
+            "B" => SkewbAxis::B,
+            _ => return Err(anyhow!("invalid Skewb move: {s}")),
+        };
+
+        Ok(SkewbMove { axis, clockwise })
+    }
+}
+
+impl Display for SkewbMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.axis)?;
+        if !self.clockwise {
+            write!(f, "'")?;
+        }
+        Ok(())
+    }
+}
+
+/// Corners at each position and their orientations after the clockwise
+/// turns of R, U, L and B, numbered and oriented as the corners of
+/// [`Cube`](super::Cube).
+const CORNER_PERMS: [[usize; 8]; 4] = [
+    [0, 6, 2, 3, 1, 5, 4, 7],
+    [0, 4, 2, 1, 3, 5, 6, 7],
+    [0, 1, 2, 4, 6, 5, 3, 7],
+    [5, 1, 2, 3, 4, 7, 6, 0],
+];
+
+const CORNER_ORIENTS: [[u8; 8]; 4] = [
+    [0, 2, 0, 0, 2, 1, 2, 0],
+    [1, 2, 0, 2, 2, 0, 0, 0],
+    [0, 0, 0, 2, 2, 0, 2, 1],
+    [2, 0, 0, 0, 1, 2, 0, 2],
+];
+
+/// Centers at each face after the clockwise turns, the faces numbered as
+/// [`Face::all`].
+const CENTER_PERMS: [[usize; 6]; 4] = [
+    [0, 3, 2, 5, 4, 1],
+    [5, 1, 2, 3, 0, 4],
+    [0, 1, 4, 2, 3, 5],
+    [0, 1, 2, 4, 5, 3],
+];
+
+/// States after each move of [`SkewbMove::all`] from the solved state.
+static MOVES: LazyLock<Vec<Skewb>> = LazyLock::new(|| {
+    SkewbMove::all()
+        .iter()
+        .map(|m| {
+            let axis = m.axis.index();
+            let turn = Skewb {
+                corners: Corners::try_from_parts(CORNER_PERMS[axis], CORNER_ORIENTS[axis]).unwrap(),
+                centers: CENTER_PERMS[axis].try_into().unwrap(),
+            };
+            // the counterclockwise turn is the clockwise turn twice
+            if m.clockwise { turn } else { &turn * &turn }
+        })
+        .collect()
+});
+
+static CORNERS: LazyLock<Enumeration<Corners>> = LazyLock::new(|| {
+    Enumeration::new(Corners::default(), 8, |corners, m| {
+        corners * &MOVES[m].corners
+    })
+});
+
+static CENTERS: LazyLock<Enumeration<SymmetricGroup<6>>> = LazyLock::new(|| {
+    Enumeration::new(SymmetricGroup::identity(), 8, |centers, m| {
+        centers * &MOVES[m].centers
+    })
+});
+
+/// Distances of all states of the Skewb to the solved state.
+static TABLE: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let size = CORNERS.len() * CENTERS.len();
+    // bump the version whenever the numbering of the states changes
+    table_cache::load_or_generate(
+        "skewb",
+        table_cache::fnv1a(b"skewb v1 R U L B"),
+        || enumeration::distances(&CORNERS, &CENTERS),
+        |table| table.clone(),
+        |bytes| (bytes.len() == size).then(|| bytes.to_vec()),
+    )
+});
+
+/// Represents Skewb state by its corners as in [`Cube`](super::Cube), with
+/// the UFR corner fixed, and the center at each face.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Skewb {
+    corners: Corners,
+    centers: SymmetricGroup<6>,
+}
+
+impl Skewb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a uniformly random state.
+    pub fn random() -> Self {
+        Self {
+            corners: CORNERS.random().clone(),
+            centers: CENTERS.random().clone(),
+        }
+    }
+
+    /// Returns a uniformly random state and the shortest moves turning the
+    /// solved Skewb into it.
+    pub fn scramble() -> (Self, Vec<SkewbMove>) {
+        let skewb = Self::random();
+        let moves = enumeration::scramble(&skewb.solve(), SkewbMove::inverse);
+
+        (skewb, moves)
+    }
+
+    /// Returns the corner piece at `position` and its orientation.
+    pub fn corner(&self, position: usize) -> (usize, u8) {
+        self.corners.corner(position)
+    }
+
+    /// Returns the color of the center on the face.
+    pub fn center(&self, face: Face) -> Face {
+        let index = Face::all().iter().position(|f| *f == face).unwrap();
+        Face::all()[self.centers[index]]
+    }
+
+    pub fn apply_move(&mut self, m: &SkewbMove) {
+        *self = self.move_applied(m);
+    }
+
+    pub fn move_applied(&self, m: &SkewbMove) -> Self {
+        self * &MOVES[m.index()]
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the number of moves of the shortest solution.
+    pub fn distance(&self) -> usize {
+        let corners = CORNERS
+            .index(&self.corners)
+            .expect("all corner states are reachable");
+        let centers = CENTERS.index(&self.centers).unwrap();
+        TABLE[corners * CENTERS.len() + centers] as usize
+    }
+
+    /// Returns the shortest moves solving the Skewb. The table of all its
+    /// states is generated on first use.
+    pub fn solve(&self) -> Vec<SkewbMove> {
+        enumeration::descend(self, &SkewbMove::all(), Self::distance, Self::move_applied)
+    }
+}
+
+impl Mul for &Skewb {
+    type Output = Skewb;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Skewb {
+            corners: &self.corners * &rhs.corners,
+            centers: &self.centers * &rhs.centers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::Puzzle;
+
+    #[test]
+    fn test_parse_sequence() -> anyhow::Result<()> {
+        let moves = SkewbMove::parse_sequence("R U' L B'")?;
+        let formatted = moves.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(formatted, ["R", "U'", "L", "B'"]);
+        assert!(SkewbMove::parse_sequence("F").is_err());
+        assert!(SkewbMove::parse_sequence("r").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_size() {
+        assert_eq!(CORNERS.len(), 8748);
+        assert_eq!(CENTERS.len(), 360);
+        // all 3,149,280 states are reachable within 11 moves
+        assert!(TABLE.iter().all(|d| *d <= 11));
+    }
+
+    #[test]
+    fn test_matches_definition() -> anyhow::Result<()> {
+        let puzzle: Puzzle = include_str!("../../puzzles/skewb.toml").parse()?;
+        let scramble = "R U' L B R' B' U L'";
+        let state = puzzle.apply_moves(&puzzle.solved(), &puzzle.parse_moves(scramble)?);
+        let mut skewb = Skewb::new();
+        for m in SkewbMove::parse_sequence(scramble)? {
+            skewb.apply_move(&m);
+        }
+
+        for i in 0..8 {
+            assert_eq!(state.piece(0, i), skewb.corner(i));
+        }
+        for (i, face) in Face::all().into_iter().enumerate() {
+            assert_eq!(Face::all()[state.piece(1, i).0], skewb.center(face));
+        }
+        assert_eq!(skewb.corner(2), (2, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve() -> anyhow::Result<()> {
+        let mut skewb = Skewb::new();
+        for m in SkewbMove::parse_sequence("R U' L B'")? {
+            skewb.apply_move(&m);
+        }
+
+        let solution = skewb.solve();
+        for m in &solution {
+            skewb.apply_move(m);
+        }
+
+        assert_eq!(solution.len(), 4);
+        assert!(skewb.is_solved());
+
+        Ok(())
+    }
+}
@@ -1,137 +1,618 @@
-use std::rc::Rc;
+use std::cell::Cell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
 
 use super::algebra::rotation::Rotation;
 use super::search;
-use super::search::{Prunable, Searchable};
-use super::{Cube, Operation};
+use super::search::{
+    Heuristic, Invertible, Keyed, MoveAutomaton, Prunable, SearchNode, Searchable, State,
+    TranspositionTable,
+};
+use super::{Cube, Goal, Operation, Subgroup};
 
+/// Cube state searched for the goal in the subgroup.
 #[derive(Debug, Clone)]
-struct SearchNode {
+struct CubeState<'a> {
     rotation: Rotation,
-    parent: Option<(Rc<SearchNode>, Operation)>,
+    /// State of the automaton of canonical sequences after the path
+    sequence: usize,
+    goal: &'a Goal,
+    subgroup: &'a Subgroup,
+    /// Number of states expanded by the search
+    expanded: &'a Cell<u64>,
+    cancellation: &'a Cancellation,
 }
 
-impl Searchable for Rc<SearchNode> {
+impl State for CubeState<'_> {
+    type Move = Operation;
+
     fn is_goal(&self) -> bool {
-        // let mut result = vec![];
-        // let mut current = self.clone();
-        // loop {
-        //     if let Some((parent, op)) = &current.parent {
-        //         result.push(*op);
-        //         current = parent.clone();
-        //     } else {
-        //         break;
-        //     }
-        // }
-        // println!("{:?}", result.into_iter().rev().collect::<Vec<_>>());
+        self.goal.is_satisfied(&self.rotation)
+    }
+
+    fn moves(&self, _last: Option<&Operation>) -> impl IntoIterator<Item = (Operation, Self)> {
+        let cancelled = self.cancellation.expand(self.expanded);
+        Operation::canonical_next(self.sequence)
+            .filter(move |(op, _)| !cancelled && self.subgroup.contains_operation(op))
+            .map(|(op, sequence)| {
+                let state = CubeState {
+                    sequence,
+                    rotation: &self.rotation * op.rotation(),
+                    goal: self.goal,
+                    subgroup: self.subgroup,
+                    expanded: self.expanded,
+                    cancellation: self.cancellation,
+                };
+                (op, state)
+            })
+    }
+}
+
+impl Keyed for CubeState<'_> {
+    type Key = Rotation;
+
+    fn key(&self) -> Self::Key {
+        self.rotation.clone()
+    }
+}
+
+impl Heuristic for CubeState<'_> {
+    fn estimate(&self) -> usize {
+        // a face turn moves only 4 edges and 4 corners, so it can make at
+        // most 4 edges and 4 corners satisfy the goal.
+        let edges = self.goal.unsolved_edges(&self.rotation);
+        let corners = self.goal.unsolved_corners(&self.rotation);
+        edges.max(corners).div_ceil(4)
+    }
+}
+
+impl Prunable for CubeState<'_> {
+    fn to_prune(&self, depth: usize, max_depth: usize) -> bool {
+        self.estimate() > max_depth - depth
+    }
+}
 
+/// Cube state without the path to it, searched by strategies which keep
+/// track of visited states by themselves.
+#[derive(Debug, Clone)]
+struct StateNode<'a> {
+    rotation: Rotation,
+    subgroup: &'a Subgroup,
+    /// Number of states expanded by the search
+    expanded: &'a Cell<u64>,
+    cancellation: &'a Cancellation,
+}
+
+impl Searchable for StateNode<'_> {
+    fn is_goal(&self) -> bool {
         self.rotation == Rotation::default()
     }
 
     fn next(&self) -> Option<impl IntoIterator<Item = Self>> {
-        let parent_operation = self.parent.as_ref().map(|(_, op)| op);
-
-        let operations = if let Some(parent_operation) = parent_operation {
-            use Operation::*;
-            match parent_operation {
-                R | R2 | R3 => vec![
-                    Operation::up(),
-                    Operation::down(),
-                    Operation::front(),
-                    Operation::back(),
-                    Operation::left(),
-                ]
-                .concat(),
-                L | L2 | L3 => vec![
-                    Operation::up(),
-                    Operation::down(),
-                    Operation::front(),
-                    Operation::back(),
-                ]
-                .concat(),
-                U | U2 | U3 => vec![
-                    Operation::right(),
-                    Operation::left(),
-                    Operation::front(),
-                    Operation::back(),
-                    Operation::down(),
-                ]
-                .concat(),
-                D | D2 | D3 => vec![
-                    Operation::right(),
-                    Operation::left(),
-                    Operation::front(),
-                    Operation::back(),
-                ]
-                .concat(),
-                F | F2 | F3 => vec![
-                    Operation::right(),
-                    Operation::left(),
-                    Operation::up(),
-                    Operation::down(),
-                    Operation::back(),
-                ]
-                .concat(),
-                B | B2 | B3 => vec![
-                    Operation::right(),
-                    Operation::left(),
-                    Operation::up(),
-                    Operation::down(),
-                ]
-                .concat(),
-            }
+        Some(self.transitions().into_iter().map(|(_, node)| node))
+    }
+}
+
+impl Keyed for StateNode<'_> {
+    type Key = Rotation;
+
+    fn key(&self) -> Self::Key {
+        self.rotation.clone()
+    }
+}
+
+impl Invertible for StateNode<'_> {
+    type Move = Operation;
+
+    fn transitions(&self) -> impl IntoIterator<Item = (Self::Move, Self)> {
+        let cancelled = self.cancellation.expand(self.expanded);
+        let operations = if cancelled {
+            &[][..]
         } else {
-            Operation::all()
+            self.subgroup.operations()
         };
+        operations.iter().map(|op| {
+            let node = StateNode {
+                rotation: &self.rotation * op.rotation(),
+                subgroup: self.subgroup,
+                expanded: self.expanded,
+                cancellation: self.cancellation,
+            };
+            (*op, node)
+        })
+    }
 
-        let nodes = operations
-            .into_iter()
-            .map(|op| {
-                let new_rotation = &self.rotation * op.rotation();
-                Rc::new(SearchNode {
-                    rotation: new_rotation,
-                    parent: Some((self.clone(), op)),
-                })
-            })
-            .collect::<Vec<_>>();
-
-        Some(nodes)
+    fn inverse(m: &Self::Move) -> Self::Move {
+        m.inverse()
     }
 }
 
-impl Prunable for Rc<SearchNode> {
-    fn to_prune(&self, depth: usize, max_depth: usize) -> bool {
-        let remaining_depth = max_depth - depth;
-        if remaining_depth == 1 {
-            self.rotation.edge_hamming_distance(&Rotation::default()) > 4
-                || self.rotation.corner_hamming_distance(&Rotation::default()) > 4
-        } else if remaining_depth == 2 {
-            self.rotation.edge_hamming_distance(&Rotation::default()) > 8
-        } else if remaining_depth == 3 {
-            self.rotation.edge_hamming_distance(&Rotation::default()) > 10
+/// Search algorithm used by [`Solver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Iterative deepening depth-first search with pruning. Uses little
+    /// memory and supports partial goals.
+    #[default]
+    IterativeDeepening,
+    /// Breadth-first search skipping visited states. Keeps every visited
+    /// state in memory.
+    BreadthFirst,
+    /// A* search guided by the number of pieces not satisfying the goal.
+    /// Keeps every visited state in memory.
+    AStar,
+    /// Breadth-first search from both the cube and the solved state meeting
+    /// in the middle. Much faster for deep solutions but keeps every visited
+    /// state in memory and requires the fully solved goal.
+    Bidirectional,
+}
+
+/// Statistics of a search by [`Solver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchStats {
+    /// Number of states whose successors were generated
+    pub nodes: u64,
+}
+
+/// Number of states expanded between checks of the deadline of a
+/// [`Cancellation`].
+const DEADLINE_INTERVAL: u64 = 1024;
+
+/// Stops the searches of a [`Solver`] when cancelled from another thread or
+/// past a deadline. Clones share the cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the searches once `deadline` is passed.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.cancel();
+        }
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Counts a state expanded and returns whether the search is cancelled,
+    /// reading the clock only every [`DEADLINE_INTERVAL`] states.
+    fn expand(&self, expanded: &Cell<u64>) -> bool {
+        let count = expanded.get() + 1;
+        expanded.set(count);
+        if count.is_multiple_of(DEADLINE_INTERVAL) {
+            self.is_cancelled()
         } else {
-            false
+            self.cancelled.load(Ordering::Relaxed)
+        }
+    }
+}
+
+/// Configuration of solving: the goal, the operations allowed, the search
+/// strategy and the maximum number of operations.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    goal: Goal,
+    subgroup: Subgroup,
+    strategy: Strategy,
+    max_depth: usize,
+    /// Capacity of the transposition table of iterative deepening, if any
+    transposition_table: Option<usize>,
+    cancellation: Cancellation,
+}
+
+impl Solver {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            goal: Goal::solved(),
+            subgroup: Subgroup::all(),
+            strategy: Strategy::default(),
+            max_depth,
+            transposition_table: None,
+            cancellation: Cancellation::default(),
+        }
+    }
+
+    pub fn with_goal(mut self, goal: Goal) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    pub fn with_subgroup(mut self, subgroup: Subgroup) -> Self {
+        self.subgroup = subgroup;
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Makes iterative deepening skip states already searched, remembering
+    /// up to `capacity` of them.
+    pub fn with_transposition_table(mut self, capacity: usize) -> Self {
+        self.transposition_table = Some(capacity);
+        self
+    }
+
+    /// Stops the searches when `cancellation` is cancelled, which makes them
+    /// fail unless a solution was found.
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Searches the shortest operations that make the cube satisfy the goal.
+    ///
+    /// Returns `Ok(None)` if no solution is found within the maximum depth,
+    /// and an error if the goal is unreachable in the subgroup, the strategy
+    /// does not support the configuration or the search is cancelled.
+    /// Reachability is only decided exactly for the pieces whose permutation
+    /// and orientation are both constrained, so `Ok(None)` may also mean that
+    /// a goal constraining only one of them for some pieces is unreachable in
+    /// the subgroup.
+    pub fn solve(&self, cube: &Cube) -> Result<Option<Vec<Operation>>> {
+        self.solve_with_stats(cube)
+            .map(|(operations, _)| operations)
+    }
+
+    /// Searches as [`Solver::solve`] and returns the statistics of the
+    /// search as well.
+    pub fn solve_with_stats(&self, cube: &Cube) -> Result<(Option<Vec<Operation>>, SearchStats)> {
+        self.subgroup.check_reachable(&cube.rotation, &self.goal)?;
+
+        let expanded = Cell::new(0);
+        let operations = match self.strategy {
+            Strategy::IterativeDeepening => self.iterative_deepening(cube, &expanded),
+            Strategy::BreadthFirst => {
+                self.path_search(cube, &expanded, search::breadth_first_search)
+            }
+            Strategy::AStar => self.path_search(cube, &expanded, search::a_star_search),
+            Strategy::Bidirectional => self.bidirectional(cube, &expanded)?,
+        };
+        if operations.is_none() && self.cancellation.is_cancelled() {
+            return Err(anyhow!("search cancelled"));
         }
+
+        Ok((
+            operations,
+            SearchStats {
+                nodes: expanded.get(),
+            },
+        ))
+    }
+
+    fn iterative_deepening(&self, cube: &Cube, expanded: &Cell<u64>) -> Option<Vec<Operation>> {
+        match self.transposition_table {
+            Some(capacity) => {
+                let mut table = TranspositionTable::new(capacity);
+                self.path_search(cube, expanded, |node, max_depth| {
+                    search::iterative_deepening_dfs_with_table(node, max_depth, &mut table)
+                })
+            }
+            None => self.path_search(cube, expanded, search::iterative_deepening_dfs),
+        }
+    }
+
+    /// Runs a search on nodes tracking their paths and returns the path to
+    /// the goal found.
+    fn path_search<'a>(
+        &'a self,
+        cube: &Cube,
+        expanded: &'a Cell<u64>,
+        search: impl FnOnce(SearchNode<CubeState<'a>>, usize) -> Option<SearchNode<CubeState<'a>>>,
+    ) -> Option<Vec<Operation>> {
+        let node = SearchNode::root(CubeState {
+            sequence: MoveAutomaton::START,
+            rotation: cube.rotation.clone(),
+            goal: &self.goal,
+            subgroup: &self.subgroup,
+            expanded,
+            cancellation: &self.cancellation,
+        });
+
+        search(node, self.max_depth).map(|node| node.path())
+    }
+
+    fn bidirectional(&self, cube: &Cube, expanded: &Cell<u64>) -> Result<Option<Vec<Operation>>> {
+        if self.goal != Goal::solved() {
+            return Err(anyhow!(
+                "bidirectional search only supports the fully solved goal"
+            ));
+        }
+
+        let start = StateNode {
+            rotation: cube.rotation.clone(),
+            subgroup: &self.subgroup,
+            expanded,
+            cancellation: &self.cancellation,
+        };
+        let goal = StateNode {
+            rotation: Rotation::default(),
+            subgroup: &self.subgroup,
+            expanded,
+            cancellation: &self.cancellation,
+        };
+
+        Ok(search::bidirectional_search(start, goal, self.max_depth))
     }
 }
 
 pub fn solve(cube: Cube, max_depth: usize) -> Option<Vec<Operation>> {
-    let node = Rc::new(SearchNode {
-        rotation: cube.rotation.clone(),
-        parent: None,
-    });
-    let result_node = search::iterative_deepening_dfs(node, max_depth)?;
-
-    let mut result = vec![];
-    let mut current = &result_node;
-    loop {
-        if let Some((parent, op)) = &current.parent {
-            result.push(*op);
-            current = &parent;
-        } else {
-            break;
+    solve_goal(cube, &Goal::solved(), max_depth)
+}
+
+/// Searches the shortest operations that make the cube satisfy `goal`.
+pub fn solve_goal(cube: Cube, goal: &Goal, max_depth: usize) -> Option<Vec<Operation>> {
+    Solver::new(max_depth)
+        .with_goal(goal.clone())
+        .iterative_deepening(&cube, &Cell::new(0))
+}
+
+/// Searches the shortest operations in `subgroup` that make the cube satisfy
+/// `goal`.
+///
+/// Returns an error if the goal is found to be unreachable in the subgroup.
+/// Goals constraining only the permutation or only the orientation of some
+/// pieces may also be unreachable and end the search without a solution.
+pub fn solve_in_subgroup(
+    cube: Cube,
+    goal: &Goal,
+    subgroup: &Subgroup,
+    max_depth: usize,
+) -> Result<Option<Vec<Operation>>> {
+    Solver::new(max_depth)
+        .with_goal(goal.clone())
+        .with_subgroup(subgroup.clone())
+        .solve(&cube)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(cube: &mut Cube, operations: &[Operation]) {
+        for op in operations {
+            cube.apply_operation(op);
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::R, Operation::U3, Operation::F2]);
+
+        let result = solve(cube.clone(), 3).unwrap();
+        apply(&mut cube, &result);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(cube, Cube::new());
+    }
+
+    #[test]
+    fn test_solve_goal_cross() {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::U, Operation::R, Operation::U2]);
+
+        let result = solve_goal(cube.clone(), &Goal::cross(), 3).unwrap();
+        apply(&mut cube, &result);
+
+        assert_eq!(result, vec![Operation::R3]);
+        assert!(Goal::cross().is_satisfied(&cube.rotation));
+    }
+
+    #[test]
+    fn test_solve_goal_edge_orientation() {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::F, Operation::R]);
+
+        let result = solve_goal(cube.clone(), &Goal::edge_orientation(), 2).unwrap();
+        apply(&mut cube, &result);
+
+        assert!(result.len() <= 2);
+        assert!(Goal::edge_orientation().is_satisfied(&cube.rotation));
+    }
+
+    #[test]
+    fn test_solve_in_subgroup() -> anyhow::Result<()> {
+        let subgroup = Subgroup::generated_by(&[Operation::R, Operation::U]);
+        let mut cube = Cube::new();
+        apply(
+            &mut cube,
+            &[Operation::R, Operation::U, Operation::R3, Operation::U3],
+        );
+
+        let result = solve_in_subgroup(cube.clone(), &Goal::solved(), &subgroup, 4)?.unwrap();
+        apply(&mut cube, &result);
+
+        assert_eq!(result.len(), 4);
+        assert!(result.iter().all(|op| subgroup.contains_operation(op)));
+        assert_eq!(cube, Cube::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_in_subgroup_unreachable() {
+        let subgroup = Subgroup::generated_by(&[Operation::R, Operation::U]);
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::F]);
+
+        assert!(solve_in_subgroup(cube, &Goal::solved(), &subgroup, 4).is_err());
+    }
+
+    #[test]
+    fn test_solver_bidirectional() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        apply(
+            &mut cube,
+            &[
+                Operation::R,
+                Operation::U3,
+                Operation::F2,
+                Operation::L,
+                Operation::D,
+            ],
+        );
+
+        let solver = Solver::new(5).with_strategy(Strategy::Bidirectional);
+        let result = solver.solve(&cube)?.unwrap();
+        apply(&mut cube, &result);
+
+        assert_eq!(result.len(), 5);
+        assert_eq!(cube, Cube::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_bidirectional_partial_goal() {
+        let solver = Solver::new(5)
+            .with_goal(Goal::cross())
+            .with_strategy(Strategy::Bidirectional);
+
+        assert!(solver.solve(&Cube::new()).is_err());
+    }
+
+    #[test]
+    fn test_solver_strategies() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::F, Operation::R3, Operation::D2]);
+
+        for strategy in [
+            Strategy::IterativeDeepening,
+            Strategy::BreadthFirst,
+            Strategy::AStar,
+            Strategy::Bidirectional,
+        ] {
+            let result = Solver::new(3)
+                .with_strategy(strategy)
+                .solve(&cube)?
+                .unwrap();
+            let mut solved = cube.clone();
+            apply(&mut solved, &result);
+
+            assert_eq!(result.len(), 3);
+            assert_eq!(solved, Cube::new());
         }
+
+        Ok(())
     }
 
-    Some(result.into_iter().rev().collect())
+    #[test]
+    fn test_solver_transposition_table() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        apply(
+            &mut cube,
+            &[
+                Operation::R,
+                Operation::U,
+                Operation::R3,
+                Operation::U3,
+                Operation::F2,
+            ],
+        );
+
+        let expected = Solver::new(5).solve(&cube)?.unwrap();
+        let result = Solver::new(5)
+            .with_transposition_table(1 << 16)
+            .solve(&cube)?
+            .unwrap();
+        apply(&mut cube, &result);
+
+        assert_eq!(result.len(), expected.len());
+        assert_eq!(cube, Cube::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_stats() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::R, Operation::U]);
+
+        let (_, solved) = Solver::new(2).solve_with_stats(&Cube::new())?;
+        let (result, stats) = Solver::new(2).solve_with_stats(&cube)?;
+        let (_, table) = Solver::new(2)
+            .with_transposition_table(1 << 10)
+            .solve_with_stats(&cube)?;
+
+        assert_eq!(result.map(|r| r.len()), Some(2));
+        assert_eq!(solved.nodes, 0);
+        assert!(stats.nodes > 0);
+        assert!(table.nodes <= stats.nodes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_cancellation() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::R, Operation::U, Operation::F]);
+
+        let cancellation = Cancellation::new();
+        cancellation.clone().cancel();
+        for strategy in [
+            Strategy::IterativeDeepening,
+            Strategy::AStar,
+            Strategy::Bidirectional,
+        ] {
+            let solver = Solver::new(3)
+                .with_strategy(strategy)
+                .with_cancellation(cancellation.clone());
+            assert!(solver.solve(&cube).is_err());
+            assert_eq!(solver.solve(&Cube::new())?, Some(vec![]));
+        }
+
+        // the deadline is read after the first states expanded
+        let expired = Cancellation::new().with_deadline(Instant::now());
+        let mut deep = cube.clone();
+        apply(&mut deep, &[Operation::D, Operation::L, Operation::B]);
+        assert!(
+            Solver::new(6)
+                .with_cancellation(expired)
+                .solve(&deep)
+                .is_err()
+        );
+        let (result, _) = Solver::new(3)
+            .with_cancellation(Cancellation::new())
+            .solve_with_stats(&cube)?;
+        assert_eq!(result.map(|r| r.len()), Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solver_a_star_partial_goal() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        apply(&mut cube, &[Operation::U, Operation::R, Operation::U2]);
+
+        let result = Solver::new(3)
+            .with_goal(Goal::cross())
+            .with_strategy(Strategy::AStar)
+            .solve(&cube)?;
+
+        assert_eq!(result, Some(vec![Operation::R3]));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+
+use anyhow::{Result, anyhow};
+
+use super::algebra::rotation::Rotation;
+use super::algebra::stabilizer_chain::{Factorization, StabilizerChain};
+use super::algebra::symmetric_group::SymmetricGroup;
+use super::{Goal, Operation};
+
+/// A subgroup of the cube group generated by a set of operations,
+/// e.g. `<R, U>` or `<U, D, R2, L2, F2, B2>`.
+///
+/// Searching in a subgroup only plays the operations obtained by repeating
+/// the generators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subgroup {
+    operations: Vec<Operation>,
+}
+
+/// Stabilizer chains of the subgroups checked by
+/// [`Subgroup::check_reachable`], by their operations and the stickers the
+/// bases start with.
+type Chains = HashMap<(Vec<Operation>, Vec<usize>), Arc<StabilizerChain<48>>>;
+
+static CHAINS: LazyLock<Mutex<Chains>> = LazyLock::new(Default::default);
+
+/// Orbits of positions of a kind of pieces under the generators.
+struct Orbits<const N: usize> {
+    /// Representative position of the orbit containing each position
+    root: [usize; N],
+    /// Whether the generators can change orientations in the orbit
+    orientable: [bool; N],
+    /// Whether the sum of orientations in the orbit is invariant
+    sum_invariant: [bool; N],
+    /// Whether all generators are even permutations
+    even: bool,
+}
+
+impl<const N: usize> Orbits<N> {
+    fn new(generators: &[(Vec<(usize, u8)>, bool)], modulus: u8) -> Self {
+        let mut root: [usize; N] = std::array::from_fn(|i| i);
+        fn find(root: &mut [usize], i: usize) -> usize {
+            let mut i = i;
+            while root[i] != i {
+                root[i] = root[root[i]];
+                i = root[i];
+            }
+            i
+        }
+
+        for (pieces, _) in generators {
+            for (position, (from, _)) in pieces.iter().enumerate() {
+                let (a, b) = (find(&mut root, position), find(&mut root, *from));
+                root[a] = b;
+            }
+        }
+        for i in 0..N {
+            root[i] = find(&mut root, i);
+        }
+
+        let mut orientable = [false; N];
+        let mut sum_invariant = [true; N];
+        for (pieces, _) in generators {
+            let mut sums = [0u8; N];
+            for (position, (_, orient)) in pieces.iter().enumerate() {
+                orientable[root[position]] |= *orient != 0;
+                sums[root[position]] = (sums[root[position]] + orient) % modulus;
+            }
+            for i in 0..N {
+                sum_invariant[i] &= sums[i] == 0;
+            }
+        }
+        let orientable = std::array::from_fn(|i| orientable[root[i]]);
+        let sum_invariant = std::array::from_fn(|i| sum_invariant[root[i]]);
+
+        Self {
+            root,
+            orientable,
+            sum_invariant,
+            even: generators.iter().all(|(_, even)| *even),
+        }
+    }
+
+    fn check(
+        &self,
+        kind: &str,
+        pieces: [(usize, u8); N],
+        perm_mask: impl Fn(usize) -> bool,
+        orient_mask: impl Fn(usize) -> bool,
+        is_even: bool,
+        modulus: u8,
+    ) -> Result<()> {
+        for (position, &(piece, orient)) in pieces.iter().enumerate() {
+            if perm_mask(piece) && self.root[position] != self.root[piece] {
+                return Err(anyhow!(
+                    "{kind} {piece} at position {position} cannot be moved to its position"
+                ));
+            }
+            if orient_mask(piece) && orient != 0 && !self.orientable[position] {
+                return Err(anyhow!(
+                    "{kind} {piece} at position {position} cannot be oriented"
+                ));
+            }
+        }
+
+        for r in (0..N).filter(|&i| self.root[i] == i && self.sum_invariant[i]) {
+            let orbit = (0..N).filter(|&i| self.root[i] == r);
+            if !orbit.clone().all(|i| orient_mask(pieces[i].0)) {
+                continue;
+            }
+            let sum = orbit.map(|i| pieces[i].1).fold(0, |a, b| (a + b) % modulus);
+            if sum != 0 {
+                return Err(anyhow!(
+                    "twisted {kind}s: orientation sum {sum} around position {r} is invariant"
+                ));
+            }
+        }
+
+        if self.even && !is_even && (0..N).all(&perm_mask) {
+            return Err(anyhow!("{kind} permutation has odd parity"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Subgroup {
+    /// Returns the subgroup generated by the given operations.
+    pub fn generated_by(generators: &[Operation]) -> Self {
+        let mut operations = vec![];
+        for operation in generators.iter().flat_map(|g| g.powers()) {
+            if !operations.contains(&operation) {
+                operations.push(operation);
+            }
+        }
+        Self { operations }
+    }
+
+    /// Returns the whole cube group.
+    pub fn all() -> Self {
+        Self {
+            operations: Operation::all(),
+        }
+    }
+
+    /// Returns the operations which can be played in the subgroup.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn contains_operation(&self, operation: &Operation) -> bool {
+        self.operations.contains(operation)
+    }
+
+    /// Checks whether `goal` can be reached from `rotation` only with the
+    /// operations of the subgroup.
+    ///
+    /// The pieces whose permutation and orientation are both constrained,
+    /// as all are in the solved goal, are checked exactly: some rotation of
+    /// the subgroup must bring their stickers home, which a stabilizer chain
+    /// whose base starts with these stickers tells. The other constraints
+    /// are only checked for necessary conditions: the orbits of the pieces,
+    /// the orientations the generators never change, the sum of
+    /// orientations in each orbit and the permutation parity.
+    pub fn check_reachable(&self, rotation: &Rotation, goal: &Goal) -> Result<()> {
+        let edges = self
+            .operations
+            .iter()
+            .map(|op| {
+                let r = op.rotation();
+                (
+                    (0..12).map(|i| r.edge(i)).collect(),
+                    r.is_edge_permutation_even(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let corners = self
+            .operations
+            .iter()
+            .map(|op| {
+                let r = op.rotation();
+                (
+                    (0..8).map(|i| r.corner(i)).collect(),
+                    r.is_corner_permutation_even(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Orbits::<12>::new(&edges, 2).check(
+            "edge",
+            std::array::from_fn(|i| rotation.edge(i)),
+            |piece| goal.edge_perm(piece),
+            |piece| goal.edge_orient(piece),
+            rotation.is_edge_permutation_even(),
+            2,
+        )?;
+        Orbits::<8>::new(&corners, 3).check(
+            "corner",
+            std::array::from_fn(|i| rotation.corner(i)),
+            |piece| goal.corner_perm(piece),
+            |piece| goal.corner_orient(piece),
+            rotation.is_corner_permutation_even(),
+            3,
+        )?;
+
+        let edge_stickers = (0..12)
+            .filter(|&piece| goal.edge_perm(piece) && goal.edge_orient(piece))
+            .flat_map(|piece| (0..2).map(move |t| 2 * piece + t));
+        let corner_stickers = (0..8)
+            .filter(|&piece| goal.corner_perm(piece) && goal.corner_orient(piece))
+            .flat_map(|piece| (0..3).map(move |t| 24 + 3 * piece + t));
+        let stickers = edge_stickers.chain(corner_stickers).collect::<Vec<_>>();
+        // the subgroup must hold a rotation taking each of these stickers
+        // to where the inverse of the rotation takes it
+        let inverse = rotation.stickers().inverse();
+        if !stickers.is_empty()
+            && !self
+                .cached_chain(&stickers)
+                .contains_on_base(&inverse, stickers.len())
+        {
+            return Err(anyhow!("the goal cannot be reached in the subgroup"));
+        }
+
+        Ok(())
+    }
+
+    fn cached_chain(&self, base_prefix: &[usize]) -> Arc<StabilizerChain<48>> {
+        let mut chains = CHAINS.lock().unwrap();
+        let chain = chains
+            .entry((self.operations.clone(), base_prefix.to_vec()))
+            .or_insert_with(|| {
+                Arc::new(StabilizerChain::with_base_prefix(
+                    &self.generators(),
+                    base_prefix,
+                ))
+            });
+        Arc::clone(chain)
+    }
+}
+
+impl Subgroup {
+    /// Computes the stabilizer chain of the subgroup by the Schreier–Sims
+    /// algorithm, giving its exact order and membership.
+    pub fn stabilizer_chain(&self) -> SubgroupChain {
+        SubgroupChain {
+            operations: self.operations.clone(),
+            chain: StabilizerChain::new(&self.generators()),
+            factorization: OnceLock::new(),
+        }
+    }
+
+    /// Returns the permutations of the stickers by the operations.
+    fn generators(&self) -> Vec<SymmetricGroup<48>> {
+        self.operations
+            .iter()
+            .map(|op| op.rotation().stickers())
+            .collect()
+    }
+}
+
+/// Stabilizer chain of a [`Subgroup`] acting on the 48 stickers of the
+/// edges and corners, as computed by [`Subgroup::stabilizer_chain`].
+#[derive(Debug, Clone)]
+pub struct SubgroupChain {
+    operations: Vec<Operation>,
+    chain: StabilizerChain<48>,
+    /// Built on first use, `None` if it could not be filled
+    factorization: OnceLock<Option<Factorization<48>>>,
+}
+
+impl SubgroupChain {
+    /// Returns the number of rotations in the subgroup, e.g. 73,483,200 for
+    /// `<R, U>`.
+    pub fn order(&self) -> u128 {
+        self.chain.order()
+    }
+
+    /// Returns true if the rotation is reached from the solved cube with
+    /// the operations of the subgroup.
+    pub fn contains(&self, rotation: &Rotation) -> bool {
+        self.chain.contains(&rotation.stickers())
+    }
+
+    /// Returns operations of the subgroup which reach the rotation from the
+    /// solved cube, or `None` if it is not in the subgroup or no words were
+    /// found for the chain.
+    ///
+    /// The operations are products of words stored for each level of the
+    /// chain, which are built on first use and are far from the shortest.
+    pub fn factorize(&self, rotation: &Rotation) -> Option<Vec<Operation>> {
+        let factorization = self.factorization.get_or_init(|| {
+            let letters = self
+                .operations
+                .iter()
+                .map(|op| op.rotation().stickers())
+                .collect::<Vec<_>>();
+            Factorization::new(&self.chain, &letters)
+        });
+
+        let word = factorization.as_ref()?.factorize(&rotation.stickers())?;
+        Some(word.iter().map(|letter| self.operations[*letter]).collect())
+    }
+}
+
+impl Default for Subgroup {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubiks_cube::Cube;
+
+    fn rotation_of(operations: &[Operation]) -> Rotation {
+        let mut cube = Cube::new();
+        for op in operations {
+            cube.apply_operation(op);
+        }
+        cube.rotation
+    }
+
+    #[test]
+    fn test_generated_by() {
+        let group = Subgroup::generated_by(&[Operation::R, Operation::U, Operation::F2]);
+
+        assert_eq!(
+            group.operations(),
+            &[
+                Operation::R,
+                Operation::R2,
+                Operation::R3,
+                Operation::U,
+                Operation::U2,
+                Operation::U3,
+                Operation::F2
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_reachable() {
+        let group = Subgroup::generated_by(&[Operation::R, Operation::U]);
+        let goal = Goal::solved();
+
+        assert!(
+            group
+                .check_reachable(&rotation_of(&[Operation::R, Operation::U3]), &goal)
+                .is_ok()
+        );
+        assert!(
+            group
+                .check_reachable(&rotation_of(&[Operation::F]), &goal)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_order() {
+        use Operation::*;
+
+        let orders = [
+            (vec![R, U], 73_483_200),
+            (vec![U, D, R2, L2, F2, B2], 19_508_428_800),
+            (vec![R2, L2, U2, D2, F2, B2], 663_552),
+            (Operation::all(), 43_252_003_274_489_856_000),
+        ];
+
+        for (generators, order) in orders {
+            let group = Subgroup::generated_by(&generators);
+            assert_eq!(group.stabilizer_chain().order(), order);
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let chain = Subgroup::generated_by(&[Operation::R, Operation::U]).stabilizer_chain();
+
+        assert!(chain.contains(&rotation_of(&[Operation::R, Operation::U3])));
+        assert!(!chain.contains(&rotation_of(&[Operation::F])));
+        // the T permutation swaps two corners and two edges of the orbits
+        // of <R, U> with matching parities, but <R, U> only reaches 120
+        // permutations of its 6 corners, none of which is a swap
+        let t_perm = Operation::parse_sequence("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap();
+        let rotation = rotation_of(&t_perm);
+        let group = Subgroup::generated_by(&[Operation::R, Operation::U]);
+        assert!(!chain.contains(&rotation));
+        assert!(group.check_reachable(&rotation, &Goal::solved()).is_err());
+    }
+
+    #[test]
+    fn test_factorize() {
+        let group = Subgroup::generated_by(&[Operation::R, Operation::U]);
+        let chain = group.stabilizer_chain();
+        let scramble = Operation::parse_sequence("R U2 R' U' R U' R' U R2 U R U' R'").unwrap();
+        let rotation = rotation_of(&scramble);
+
+        let operations = chain.factorize(&rotation).unwrap();
+
+        assert_eq!(rotation_of(&operations), rotation);
+        assert!(operations.iter().all(|op| group.contains_operation(op)));
+        assert!(chain.factorize(&rotation_of(&[Operation::F])).is_none());
+    }
+
+    #[test]
+    fn test_check_reachable_partial() {
+        let group = Subgroup::generated_by(&[Operation::R, Operation::U]);
+        let t_perm = Operation::parse_sequence("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap();
+        let corners = Goal::none().with_corners(&[0, 1, 2, 3, 4, 5, 6, 7]);
+
+        assert!(
+            group
+                .check_reachable(&rotation_of(&t_perm), &corners)
+                .is_err()
+        );
+        // D does not move the corners of the top layer, which R' U brings
+        // back after U' R
+        let top = Goal::none().with_corners(&[0, 1, 2, 3]);
+        let rotation = rotation_of(&[Operation::D, Operation::U3, Operation::R]);
+        assert!(group.check_reachable(&rotation, &top).is_ok());
+        assert!(group.check_reachable(&rotation, &Goal::solved()).is_err());
+    }
+
+    #[test]
+    fn test_check_reachable_orientation() {
+        let group = Subgroup::generated_by(&[
+            Operation::U,
+            Operation::D,
+            Operation::R2,
+            Operation::L2,
+            Operation::F2,
+            Operation::B2,
+        ]);
+        let rotation = rotation_of(&[Operation::R, Operation::U, Operation::R3]);
+
+        assert!(group.check_reachable(&rotation, &Goal::solved()).is_err());
+        assert!(
+            group
+                .check_reachable(&rotation, &Goal::edge_orientation())
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use super::facelet::STICKERS;
+use super::{ColorScheme, Cube, Face, Facelets, Operation};
+
+/// Size of a sticker in the images in pixels.
+const SIZE: f64 = 30.0;
+
+/// Color of the masked stickers.
+const MASKED: [u8; 3] = [136, 136, 136];
+
+/// Color of the plastic between the stickers.
+const BODY: &str = "#1a1a1a";
+
+/// SVG images of the stickers of the cube: the unfolded net, the top layer
+/// seen from above with the side stickers of the layer, as for OLL and PLL
+/// cases, and an isometric view of U, F and R.
+///
+/// Masked stickers, which do not matter for a case, are drawn gray.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Svg {
+    facelets: Facelets,
+    colors: ColorScheme,
+    masked: [bool; 54],
+}
+
+impl Svg {
+    pub fn new(facelets: Facelets) -> Self {
+        Self {
+            facelets,
+            colors: ColorScheme::default(),
+            masked: [false; 54],
+        }
+    }
+
+    /// Returns the images of the state solved by the operations, e.g. the
+    /// case of an algorithm.
+    pub fn for_algorithm(operations: &[Operation]) -> Self {
+        let mut cube = Cube::new();
+        for operation in operations.iter().rev() {
+            cube.apply_operation(&operation.inverse());
+        }
+        cube.svg()
+    }
+
+    pub fn with_colors(mut self, colors: ColorScheme) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Masks the stickers for which `mask` returns true given the index of
+    /// the sticker in the facelet order and its color, in addition to the
+    /// stickers already masked.
+    pub fn with_mask(mut self, mask: impl Fn(usize, Face) -> bool) -> Self {
+        for (i, color) in self.facelets.colors().iter().enumerate() {
+            self.masked[i] |= mask(i, *color);
+        }
+        self
+    }
+
+    fn fill(&self, index: usize) -> String {
+        let [r, g, b] = if self.masked[index] {
+            MASKED
+        } else {
+            self.colors.color(self.facelets.colors()[index])
+        };
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+
+    /// Returns the unfolded net, U above L, F, R and B above D.
+    pub fn net(&self) -> String {
+        let mut body = String::new();
+        let origins = [(3, 0), (6, 3), (3, 3), (3, 6), (0, 3), (9, 3)];
+        for (i, (col, row)) in origins.into_iter().enumerate() {
+            let (x, y) = (col as f64 * SIZE, row as f64 * SIZE);
+            rect(&mut body, x, y, 3.0 * SIZE, 3.0 * SIZE, BODY);
+            for k in 0..9 {
+                let (x, y) = (x + (k % 3) as f64 * SIZE, y + (k / 3) as f64 * SIZE);
+                sticker(&mut body, x, y, SIZE, SIZE, &self.fill(i * 9 + k));
+            }
+        }
+
+        document(12.0 * SIZE, 9.0 * SIZE, &body)
+    }
+
+    /// Returns the top layer seen from above, with F at the bottom and the
+    /// side stickers of the layer as thin strips around U.
+    pub fn top_layer(&self) -> String {
+        let strip = SIZE / 3.0;
+        let mut body = String::new();
+        rect(&mut body, strip, strip, 3.0 * SIZE, 3.0 * SIZE, BODY);
+
+        for (i, (position, normal)) in STICKERS.iter().enumerate() {
+            if position[1] != 1 {
+                continue;
+            }
+            // the column and row of the cubie seen from above
+            let (col, row) = ((position[0] + 1) as f64, (position[2] + 1) as f64);
+            let (x, y) = (strip + col * SIZE, strip + row * SIZE);
+            let (x, y, width, height) = match normal {
+                [0, 1, 0] => (x, y, SIZE, SIZE),
+                [1, 0, 0] => (x + SIZE, y, strip, SIZE),
+                [-1, 0, 0] => (x - strip, y, strip, SIZE),
+                [0, 0, 1] => (x, y + SIZE, SIZE, strip),
+                _ => (x, y - strip, SIZE, strip),
+            };
+            sticker(&mut body, x, y, width, height, &self.fill(i));
+        }
+
+        let size = 3.0 * SIZE + 2.0 * strip;
+        document(size, size, &body)
+    }
+
+    /// Returns the isometric view of the cube from the corner between U, F
+    /// and R.
+    pub fn isometric(&self) -> String {
+        let scale = SIZE * 1.2;
+        let project = |p: [f64; 3]| {
+            let x = (p[0] - p[2]) * 30f64.to_radians().cos() * scale;
+            let y = ((p[0] + p[2]) * 0.5 - p[1]) * scale;
+            // move the origin to the top left of the image
+            (x + 3.0 * scale, y + 3.0 * scale)
+        };
+
+        let mut body = String::new();
+        for (i, (position, normal)) in STICKERS.iter().enumerate() {
+            if !matches!(normal, [0, 1, 0] | [1, 0, 0] | [0, 0, 1]) {
+                continue;
+            }
+            // the two directions along the face
+            let axes: Vec<[f64; 3]> = (0..3)
+                .filter(|k| normal[*k] == 0)
+                .map(|k| std::array::from_fn(|j| (j == k) as u8 as f64))
+                .collect();
+            let center: [f64; 3] =
+                std::array::from_fn(|k| position[k] as f64 + normal[k] as f64 * 0.5);
+            let corner = |a: f64, b: f64| {
+                project(std::array::from_fn(|k| {
+                    center[k] + a * axes[0][k] + b * axes[1][k]
+                }))
+            };
+
+            let outer = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)];
+            polygon(&mut body, outer.map(|(a, b)| corner(a, b)), BODY);
+            let inner = outer.map(|(a, b)| corner(a * 0.86, b * 0.86));
+            polygon(&mut body, inner, &self.fill(i));
+        }
+
+        document(6.0 * scale, 6.0 * scale, &body)
+    }
+}
+
+impl Cube {
+    /// Returns the SVG images of the cube.
+    pub fn svg(&self) -> Svg {
+        Svg::new(Facelets::from_rotation(&self.rotation))
+    }
+}
+
+fn document(width: f64, height: f64, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n{body}</svg>\n"
+    )
+}
+
+fn rect(out: &mut String, x: f64, y: f64, width: f64, height: f64, fill: &str) {
+    writeln!(
+        out,
+        "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{width:.1}\" height=\"{height:.1}\" fill=\"{fill}\"/>"
+    )
+    .unwrap();
+}
+
+/// Draws a sticker inside its cell, leaving a border of the body.
+fn sticker(out: &mut String, x: f64, y: f64, width: f64, height: f64, fill: &str) {
+    let border = SIZE * 0.07;
+    writeln!(
+        out,
+        "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" rx=\"{:.1}\" \
+         fill=\"{fill}\" stroke=\"{BODY}\" stroke-width=\"{:.1}\"/>",
+        x,
+        y,
+        width,
+        height,
+        border,
+        border * 2.0,
+    )
+    .unwrap();
+}
+
+fn polygon(out: &mut String, points: [(f64, f64); 4], fill: &str) {
+    let points = points
+        .iter()
+        .map(|(x, y)| format!("{x:.1},{y:.1}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(out, "<polygon points=\"{points}\" fill=\"{fill}\"/>").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views() {
+        let svg = Cube::new().svg();
+
+        let net = svg.net();
+        assert!(net.starts_with("<svg "));
+        assert_eq!(net.matches("rx=").count(), 54);
+        assert_eq!(net.matches("#ffffff").count(), 9);
+        assert_eq!(svg.top_layer().matches("rx=").count(), 21);
+        assert_eq!(svg.isometric().matches("<polygon").count(), 54);
+    }
+
+    #[test]
+    fn test_mask() -> anyhow::Result<()> {
+        let sune = Operation::parse_sequence("R U R' U R U2 R'")?;
+        let svg = Svg::for_algorithm(&sune).with_mask(|_, color| color != Face::U);
+        let top = svg.top_layer();
+
+        // the stickers of the top layer but the 9 of U's color are masked
+        assert_eq!(top.matches("#ffffff").count(), 9);
+        assert_eq!(top.matches("#888888").count(), 12);
+
+        Ok(())
+    }
+}
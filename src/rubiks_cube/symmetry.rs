@@ -0,0 +1,323 @@
+use std::ops::Mul;
+use std::sync::LazyLock;
+
+use super::Operation;
+use super::algebra::rotation::Rotation;
+use super::coordinate;
+use super::facelet::{CORNER_STICKERS, EDGE_STICKERS, Face, STICKERS, sticker_index};
+
+/// A symmetry of the cube, i.e. one of the 24 rotations of the whole cube
+/// or one of the 24 rotations followed by the reflection through the plane
+/// between L and R.
+///
+/// A symmetry `S` acts on states by conjugation `S X S⁻¹`: the cube is
+/// turned or mirrored by `S` and its stickers are recolored so that the
+/// centers match again. The conjugate of an operation is an operation, so
+/// the conjugate of a state is solved by the conjugated solution.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Symmetry {
+    /// Signed permutation matrix acting on vectors with x toward R, y
+    /// toward U and z toward F
+    matrix: [[i8; 3]; 3],
+    /// Position where each edge position is moved, with the index of the
+    /// sticker where its reference sticker goes
+    edges: [(usize, u8); 12],
+    /// Position where each corner position is moved, with the index of the
+    /// sticker where its U or D sticker goes
+    corners: [(usize, u8); 8],
+    reflection: bool,
+}
+
+/// All symmetries, starting with the identity and the rotations.
+static SYMMETRIES: LazyLock<Vec<Symmetry>> = LazyLock::new(|| {
+    let mut result = vec![];
+    for axes in [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ] {
+        for signs in 0..8 {
+            let mut matrix = [[0; 3]; 3];
+            for (i, axis) in axes.iter().enumerate() {
+                matrix[i][*axis] = if signs >> i & 1 == 0 { 1 } else { -1 };
+            }
+            result.push(Symmetry::new(matrix));
+        }
+    }
+    result.sort_by_key(|s| s.is_reflection());
+    result
+});
+
+impl Symmetry {
+    fn new(matrix: [[i8; 3]; 3]) -> Self {
+        let apply = |v: [i8; 3]| -> [i8; 3] {
+            std::array::from_fn(|i| (0..3).map(|j| matrix[i][j] * v[j]).sum())
+        };
+        let image = |i: usize| {
+            let (position, normal) = STICKERS[i];
+            sticker_index(&(apply(position), apply(normal)))
+        };
+        let edges = EDGE_STICKERS.map(|stickers| {
+            let target = image(stickers[0]);
+            EDGE_STICKERS
+                .iter()
+                .enumerate()
+                .find_map(|(j, s)| Some((j, s.iter().position(|i| *i == target)? as u8)))
+                .unwrap()
+        });
+        let corners = CORNER_STICKERS.map(|stickers| {
+            let target = image(stickers[0]);
+            CORNER_STICKERS
+                .iter()
+                .enumerate()
+                .find_map(|(j, s)| Some((j, s.iter().position(|i| *i == target)? as u8)))
+                .unwrap()
+        });
+
+        let m = &matrix;
+        let determinant = (0..3)
+            .map(|i| {
+                m[0][i]
+                    * (m[1][(i + 1) % 3] * m[2][(i + 2) % 3]
+                        - m[1][(i + 2) % 3] * m[2][(i + 1) % 3])
+            })
+            .sum::<i8>();
+
+        Self {
+            matrix,
+            edges,
+            corners,
+            reflection: determinant < 0,
+        }
+    }
+
+    pub fn identity() -> Self {
+        SYMMETRIES[0].clone()
+    }
+
+    /// Returns the 48 symmetries of the cube.
+    pub fn all() -> Vec<Symmetry> {
+        SYMMETRIES.clone()
+    }
+
+    /// Returns the 24 rotations of the whole cube.
+    pub fn rotations() -> Vec<Symmetry> {
+        SYMMETRIES
+            .iter()
+            .filter(|s| !s.is_reflection())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the 16 symmetries which keep the U-D axis, i.e. map U and D
+    /// stickers to U and D stickers.
+    pub fn preserving_ud() -> Vec<Symmetry> {
+        SYMMETRIES
+            .iter()
+            .filter(|s| s.matrix[1][1] != 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns true if the symmetry mirrors the cube, turning clockwise
+    /// operations into counterclockwise ones.
+    pub fn is_reflection(&self) -> bool {
+        self.reflection
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.matrix[j][i])
+        }))
+    }
+
+    /// Returns the face where the symmetry moves the face.
+    pub fn face(&self, face: Face) -> Face {
+        let n = face.normal();
+        let normal = std::array::from_fn(|i| (0..3).map(|j| self.matrix[i][j] * n[j]).sum());
+        Face::from_normal(normal).unwrap()
+    }
+
+    /// Returns the conjugate `S X S⁻¹` of the state.
+    ///
+    /// The piece at a position moves to the image of the position and
+    /// becomes the image of the piece. Its orientation is shifted by where
+    /// the reference stickers of both go, and reversed by reflections since
+    /// they turn the clockwise order of corner stickers counterclockwise.
+    pub fn conjugate(&self, rotation: &Rotation) -> Rotation {
+        let mut edge_perm = [0; 12];
+        let mut edge_orient = [0; 12];
+        for (i, (j, a)) in self.edges.iter().enumerate() {
+            let (piece, orient) = rotation.edge(i);
+            let (image, b) = self.edges[piece];
+            edge_perm[*j] = image;
+            edge_orient[*j] = (orient + a + b) % 2;
+        }
+
+        let mut corner_perm = [0; 8];
+        let mut corner_orient = [0; 8];
+        for (i, (j, a)) in self.corners.iter().enumerate() {
+            let (piece, orient) = rotation.corner(i);
+            let (image, b) = self.corners[piece];
+            corner_perm[*j] = image;
+            corner_orient[*j] = if self.reflection {
+                (a + 6 - b - orient) % 3
+            } else {
+                (a + 3 - b + orient) % 3
+            };
+        }
+
+        Rotation::try_from_parts(edge_perm, edge_orient, corner_perm, corner_orient)
+            .expect("conjugates of states are states")
+    }
+
+    /// Returns the conjugate of the operation.
+    pub fn conjugate_operation(&self, operation: &Operation) -> Operation {
+        let rotation = self.conjugate(operation.rotation());
+        Operation::all()
+            .into_iter()
+            .find(|op| *op.rotation() == rotation)
+            .expect("conjugates of operations are operations")
+    }
+
+    /// Returns the conjugate of the state with the smallest key among the
+    /// symmetries, with the symmetry giving it. States equivalent by the
+    /// symmetries have the same representative.
+    pub fn representative<'a>(
+        rotation: &Rotation,
+        symmetries: &'a [Symmetry],
+    ) -> (Rotation, &'a Symmetry) {
+        symmetries
+            .iter()
+            .map(|s| (s.conjugate(rotation), s))
+            .min_by_key(|(r, _)| key(r))
+            .expect("symmetries must not be empty")
+    }
+}
+
+/// Total order of states used to pick representatives.
+fn key(rotation: &Rotation) -> [usize; 4] {
+    [
+        coordinate::corner_permutation(rotation),
+        coordinate::edge_permutation(rotation),
+        coordinate::corner_orientation(rotation),
+        coordinate::edge_orientation(rotation),
+    ]
+}
+
+impl Mul for &Symmetry {
+    type Output = Symmetry;
+
+    /// Returns the symmetry applying `rhs` first and then `self`.
+    fn mul(self, rhs: Self) -> Self::Output {
+        Symmetry::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| (0..3).map(|k| self.matrix[i][k] * rhs.matrix[k][j]).sum())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rubiks_cube::Facelets;
+    use std::collections::HashSet;
+
+    fn rotation_of(operations: &[Operation]) -> Rotation {
+        let mut rotation = Rotation::default();
+        for op in operations {
+            rotation *= op.rotation();
+        }
+        rotation
+    }
+
+    #[test]
+    fn test_counts() {
+        assert_eq!(Symmetry::all().len(), 48);
+        assert_eq!(Symmetry::rotations().len(), 24);
+        assert_eq!(Symmetry::preserving_ud().len(), 16);
+        assert_eq!(Symmetry::all().iter().collect::<HashSet<_>>().len(), 48);
+    }
+
+    #[test]
+    fn test_conjugate_operation() {
+        for s in Symmetry::all() {
+            let conjugates = Operation::all()
+                .iter()
+                .map(|op| s.conjugate_operation(op))
+                .collect::<HashSet<_>>();
+            assert_eq!(conjugates.len(), 18);
+        }
+
+        let mirror = Symmetry::all()
+            .into_iter()
+            .find(|s| {
+                s.face(Face::R) == Face::L
+                    && s.face(Face::U) == Face::U
+                    && s.face(Face::F) == Face::F
+            })
+            .unwrap();
+        assert!(mirror.is_reflection());
+        assert_eq!(mirror.conjugate_operation(&Operation::R), Operation::L3);
+        assert_eq!(mirror.conjugate_operation(&Operation::U), Operation::U3);
+    }
+
+    #[test]
+    fn test_conjugate_homomorphism() {
+        let operations = [Operation::R, Operation::U3, Operation::F2, Operation::B];
+        let rotation = rotation_of(&operations);
+        for s in Symmetry::all() {
+            let conjugated = operations.map(|op| s.conjugate_operation(&op));
+            assert_eq!(s.conjugate(&rotation), rotation_of(&conjugated));
+        }
+    }
+
+    /// Conjugates the state by moving and recoloring its stickers.
+    fn conjugate_stickers(s: &Symmetry, rotation: &Rotation) -> Rotation {
+        let facelets = Facelets::from_rotation(rotation);
+        let mut colors = *facelets.colors();
+        for (i, color) in facelets.colors().iter().enumerate() {
+            let (position, normal) = STICKERS[i];
+            let apply = |v: [i8; 3]| -> [i8; 3] {
+                std::array::from_fn(|k| (0..3).map(|l| s.matrix[k][l] * v[l]).sum())
+            };
+            colors[sticker_index(&(apply(position), apply(normal)))] = s.face(*color);
+        }
+        Facelets::new(colors).to_rotation().unwrap()
+    }
+
+    #[test]
+    fn test_conjugate_stickers() {
+        let rotation = rotation_of(&[
+            Operation::R,
+            Operation::U,
+            Operation::F3,
+            Operation::L2,
+            Operation::D,
+        ]);
+        for s in Symmetry::all() {
+            assert_eq!(s.conjugate(&rotation), conjugate_stickers(&s, &rotation));
+        }
+    }
+
+    #[test]
+    fn test_inverse_and_mul() {
+        let rotation = rotation_of(&[Operation::R, Operation::U, Operation::F3]);
+        for s in Symmetry::all() {
+            assert_eq!(&s * &s.inverse(), Symmetry::identity());
+            assert_eq!(s.inverse().conjugate(&s.conjugate(&rotation)), rotation);
+        }
+    }
+
+    #[test]
+    fn test_representative() {
+        let symmetries = Symmetry::all();
+        let (r, _) = Symmetry::representative(&rotation_of(&[Operation::R]), &symmetries);
+        let (l, s) = Symmetry::representative(&rotation_of(&[Operation::L3]), &symmetries);
+
+        assert_eq!(r, l);
+        assert_eq!(s.conjugate(&rotation_of(&[Operation::L3])), l);
+    }
+}
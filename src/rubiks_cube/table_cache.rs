@@ -0,0 +1,210 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use anyhow::{Result, anyhow};
+
+/// Magic bytes at the beginning of table files.
+const MAGIC: &[u8; 4] = b"RCTB";
+
+/// Version of the file format, bumped whenever the layout changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// magic (4) + version (4) + fingerprint (8) + length (8) + checksum (8)
+const HEADER_SIZE: usize = 32;
+
+/// Environment variable giving the default directory of table files.
+pub const TABLE_DIR_ENV: &str = "RUBIKS_CUBE_TABLE_DIR";
+
+static TABLE_DIRECTORY: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Sets the directory where generated tables are stored and looked up.
+pub fn set_table_directory(directory: impl Into<PathBuf>) {
+    *TABLE_DIRECTORY.write().unwrap() = Some(directory.into());
+}
+
+/// Returns the directory of table files: the one set by
+/// [`set_table_directory`], or else the one in `RUBIKS_CUBE_TABLE_DIR`.
+/// Tables are not persisted if neither is given.
+pub fn table_directory() -> Option<PathBuf> {
+    if let Some(directory) = TABLE_DIRECTORY.read().unwrap().as_ref() {
+        return Some(directory.clone());
+    }
+    std::env::var_os(TABLE_DIR_ENV).map(PathBuf::from)
+}
+
+/// 64-bit FNV-1a hash, used both as the checksum of payloads and for the
+/// fingerprints of table parameters.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Writes the payload with the header to the file.
+///
+/// The file is written under a temporary name and renamed, so that readers
+/// never see a partially written table.
+pub fn save(path: &Path, fingerprint: u64, payload: &[u8]) -> Result<()> {
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + payload.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&fingerprint.to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&fnv1a(payload).to_le_bytes());
+    bytes.extend_from_slice(payload);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&temporary, bytes)?;
+    fs::rename(&temporary, path)?;
+
+    Ok(())
+}
+
+/// Reads the payload of the file, checking that it was written in the
+/// current format for the same fingerprint and is not corrupted.
+pub fn load(path: &Path, fingerprint: u64) -> Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < HEADER_SIZE || &bytes[0..4] != MAGIC {
+        return Err(anyhow!("{} is not a table file", path.display()));
+    }
+
+    let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+    let u64_at = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+    if u32_at(4) != FORMAT_VERSION {
+        return Err(anyhow!(
+            "{} has format version {}",
+            path.display(),
+            u32_at(4)
+        ));
+    }
+    if u64_at(8) != fingerprint {
+        return Err(anyhow!("{} is stale", path.display()));
+    }
+
+    let payload = &bytes[HEADER_SIZE..];
+    if u64_at(16) != payload.len() as u64 {
+        return Err(anyhow!("{} is truncated", path.display()));
+    }
+    if u64_at(24) != fnv1a(payload) {
+        return Err(anyhow!("{} has a checksum mismatch", path.display()));
+    }
+
+    Ok(payload.to_vec())
+}
+
+/// Loads the table `name` from the table directory, or generates it when
+/// the file is missing, stale or corrupted and stores it for the next time.
+///
+/// `decode` may reject a payload, which is then regenerated as well. Failing
+/// to store the table is not an error since it only costs regeneration.
+pub fn load_or_generate<T>(
+    name: &str,
+    fingerprint: u64,
+    generate: impl FnOnce() -> T,
+    encode: impl FnOnce(&T) -> Vec<u8>,
+    decode: impl FnOnce(&[u8]) -> Option<T>,
+) -> T {
+    let path = table_directory().map(|directory| directory.join(format!("{name}.tbl")));
+    load_or_generate_at(path.as_deref(), fingerprint, generate, encode, decode)
+}
+
+fn load_or_generate_at<T>(
+    path: Option<&Path>,
+    fingerprint: u64,
+    generate: impl FnOnce() -> T,
+    encode: impl FnOnce(&T) -> Vec<u8>,
+    decode: impl FnOnce(&[u8]) -> Option<T>,
+) -> T {
+    let Some(path) = path else {
+        return generate();
+    };
+
+    if let Some(table) = load(path, fingerprint).ok().and_then(|p| decode(&p)) {
+        return table;
+    }
+
+    let table = generate();
+    let _ = save(path, fingerprint, &encode(&table));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_file(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("rubiks-cube-test-{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn test_save_load() -> anyhow::Result<()> {
+        let path = temporary_file("save_load.tbl");
+        save(&path, 42, &[1, 2, 3, 4])?;
+
+        assert_eq!(load(&path, 42)?, vec![1, 2, 3, 4]);
+        assert!(load(&path, 43).is_err());
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_corrupted() -> anyhow::Result<()> {
+        let path = temporary_file("corrupted.tbl");
+        save(&path, 42, &[1, 2, 3, 4])?;
+
+        let mut bytes = fs::read(&path)?;
+        *bytes.last_mut().unwrap() = 5;
+        fs::write(&path, &bytes)?;
+        assert!(load(&path, 42).is_err());
+
+        bytes.pop();
+        fs::write(&path, &bytes)?;
+        assert!(load(&path, 42).is_err());
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_missing() {
+        assert!(load(&temporary_file("missing.tbl"), 42).is_err());
+    }
+
+    fn cached(path: &Path, value: u8) -> u8 {
+        load_or_generate_at(
+            Some(path),
+            42,
+            || value,
+            |v| vec![*v],
+            |bytes| bytes.first().copied(),
+        )
+    }
+
+    #[test]
+    fn test_load_or_generate() -> anyhow::Result<()> {
+        let path = temporary_file("load_or_generate.tbl");
+
+        assert_eq!(cached(&path, 1), 1);
+        assert_eq!(cached(&path, 2), 1);
+
+        save(&path, 43, &[3])?;
+        assert_eq!(cached(&path, 4), 4);
+        assert_eq!(load(&path, 42)?, vec![4]);
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,378 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+use anyhow::{Result, anyhow};
+
+use super::algebra::rotation::Rotation;
+use super::coordinate::{self, DistanceTable, RawCoordinate, SymmetricDistanceTable};
+use super::search::{self, Heuristic, MoveAutomaton, Prunable, SearchNode, State};
+use super::{Cube, Operation, SearchStats, Symmetry, table_cache};
+
+/// Distance table of a phase, reduced by symmetry when the coordinates and
+/// operations of the phase allow it.
+enum PhaseTable {
+    Full(DistanceTable),
+    Symmetric(SymmetricDistanceTable),
+}
+
+impl PhaseTable {
+    fn distance(&self, rotation: &Rotation) -> Option<usize> {
+        match self {
+            PhaseTable::Full(table) => table.distance(rotation),
+            PhaseTable::Symmetric(table) => table.distance(rotation),
+        }
+    }
+
+    #[cfg(test)]
+    fn size(&self) -> usize {
+        match self {
+            PhaseTable::Full(table) => table.size(),
+            PhaseTable::Symmetric(table) => table.size(),
+        }
+    }
+}
+
+/// A phase of Thistlethwaite's algorithm which brings the cube from a
+/// subgroup into the next one, playing only the operations of the former.
+struct Phase {
+    /// Name of the subgroup reached by the phase
+    subgroup: &'static str,
+    operations: Vec<Operation>,
+    table: PhaseTable,
+    max_depth: usize,
+}
+
+impl Phase {
+    /// Loads the distance table of the phase from the table directory, or
+    /// generates it. The table is reduced by the symmetries if any.
+    fn new(
+        name: &str,
+        subgroup: &'static str,
+        operations: Vec<Operation>,
+        raws: &[RawCoordinate],
+        symmetries: &[Symmetry],
+        max_depth: usize,
+    ) -> Self {
+        // bump the version whenever the coordinates change
+        let parameters = format!(
+            "{name} v1 {subgroup} {} symmetries {}",
+            operations
+                .iter()
+                .map(|op| op.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            symmetries.len()
+        );
+        let fingerprint = table_cache::fnv1a(parameters.as_bytes());
+        let table = if symmetries.is_empty() {
+            PhaseTable::Full(table_cache::load_or_generate(
+                name,
+                fingerprint,
+                || DistanceTable::new(raws, &operations),
+                DistanceTable::to_bytes,
+                |bytes| DistanceTable::from_bytes(raws, bytes),
+            ))
+        } else {
+            PhaseTable::Symmetric(table_cache::load_or_generate(
+                name,
+                fingerprint,
+                || SymmetricDistanceTable::new(raws, &operations, symmetries),
+                SymmetricDistanceTable::to_bytes,
+                |bytes| SymmetricDistanceTable::from_bytes(raws, &operations, symmetries, bytes),
+            ))
+        };
+
+        Self {
+            subgroup,
+            operations,
+            table,
+            max_depth,
+        }
+    }
+}
+
+use Operation::*;
+
+/// Names of the subgroups reached by the phases.
+pub(super) const SUBGROUPS: [&str; 4] = [
+    "G1 = <L, R, F2, B2, U, D>",
+    "G2 = <L2, R2, F2, B2, U, D>",
+    "G3 = <L2, R2, F2, B2, U2, D2>",
+    "G4 = {e}",
+];
+
+/// G0 = <L, R, F, B, U, D> to G1 = <L, R, F2, B2, U, D>: orient all edges.
+static PHASE1: LazyLock<Phase> = LazyLock::new(|| {
+    Phase::new(
+        "thistlethwaite-phase1",
+        SUBGROUPS[0],
+        Operation::all(),
+        &[coordinate::edge_orientation],
+        &[],
+        7,
+    )
+});
+
+/// G1 to G2 = <L2, R2, F2, B2, U, D>: orient all corners and bring the
+/// E-slice edges into the E slice.
+static PHASE2: LazyLock<Phase> = LazyLock::new(|| {
+    Phase::new(
+        "thistlethwaite-phase2",
+        SUBGROUPS[1],
+        vec![R, R2, R3, L, L2, L3, U, U2, U3, D, D2, D3, F2, B2],
+        &[coordinate::corner_orientation, e_slice],
+        &[],
+        10,
+    )
+});
+
+/// G2 to G3 = <L2, R2, F2, B2, U2, D2>: bring the corners into their
+/// tetrads reachable by half turns and the M-slice edges into the M slice.
+static PHASE3: LazyLock<Phase> = LazyLock::new(|| {
+    Phase::new(
+        "thistlethwaite-phase3",
+        SUBGROUPS[2],
+        vec![R2, L2, U, U2, U3, D, D2, D3, F2, B2],
+        &[corner_coset, m_slice],
+        &[],
+        13,
+    )
+});
+
+/// G3 to G4 = {e}: solve the cube with half turns. The half turns and the
+/// permutations are kept by all symmetries, so the table is reduced by them
+/// over the classes of edge permutations, the larger coordinate.
+static PHASE4: LazyLock<Phase> = LazyLock::new(|| {
+    Phase::new(
+        "thistlethwaite-phase4",
+        SUBGROUPS[3],
+        vec![R2, L2, U2, D2, F2, B2],
+        &[coordinate::edge_permutation, coordinate::corner_permutation],
+        &Symmetry::all(),
+        15,
+    )
+});
+
+/// Corner permutations of G3, which are reachable by half turns.
+static HALF_TURN_CORNERS: LazyLock<Vec<Rotation>> = LazyLock::new(|| {
+    let mut visited = HashSet::from([coordinate::corner_permutation(&Rotation::default())]);
+    let mut result = vec![Rotation::default()];
+    let mut i = 0;
+    while i < result.len() {
+        for op in [R2, L2, U2, D2, F2, B2] {
+            let next = &result[i] * op.rotation();
+            if visited.insert(coordinate::corner_permutation(&next)) {
+                result.push(next);
+            }
+        }
+        i += 1;
+    }
+    result
+});
+
+fn e_slice(rotation: &Rotation) -> usize {
+    coordinate::edge_positions(rotation, &[0, 1, 2, 3])
+}
+
+fn m_slice(rotation: &Rotation) -> usize {
+    coordinate::edge_positions(rotation, &[4, 6, 8, 10])
+}
+
+/// Identifies corner permutations which differ by a permutation of G3
+/// applied first, so that the solved class is exactly G3.
+fn corner_coset(rotation: &Rotation) -> usize {
+    HALF_TURN_CORNERS
+        .iter()
+        .map(|h| coordinate::corner_permutation(&(h * rotation)))
+        .min()
+        .unwrap()
+}
+
+#[derive(Clone)]
+struct PhaseState<'a> {
+    rotation: Rotation,
+    /// State of the automaton of canonical sequences after the path
+    sequence: usize,
+    phase: &'a Phase,
+    /// Number of states expanded by the search
+    expanded: &'a Cell<u64>,
+}
+
+impl State for PhaseState<'_> {
+    type Move = Operation;
+
+    fn is_goal(&self) -> bool {
+        self.estimate() == 0
+    }
+
+    fn moves(&self, _last: Option<&Operation>) -> impl IntoIterator<Item = (Operation, Self)> {
+        self.expanded.set(self.expanded.get() + 1);
+        Operation::canonical_next(self.sequence)
+            .filter(|(op, _)| self.phase.operations.contains(op))
+            .map(|(op, sequence)| {
+                let state = PhaseState {
+                    sequence,
+                    rotation: &self.rotation * op.rotation(),
+                    phase: self.phase,
+                    expanded: self.expanded,
+                };
+                (op, state)
+            })
+    }
+}
+
+impl Heuristic for PhaseState<'_> {
+    fn estimate(&self) -> usize {
+        self.phase
+            .table
+            .distance(&self.rotation)
+            .unwrap_or(usize::MAX)
+    }
+}
+
+impl Prunable for PhaseState<'_> {
+    fn to_prune(&self, depth: usize, max_depth: usize) -> bool {
+        self.estimate() > max_depth - depth
+    }
+}
+
+/// Operations of a phase of Thistlethwaite's algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PhaseSolution {
+    /// The subgroup reached after the operations
+    pub subgroup: &'static str,
+    pub operations: Vec<Operation>,
+}
+
+/// Solution by Thistlethwaite's algorithm split into its four phases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThistlethwaiteSolution {
+    pub phases: Vec<PhaseSolution>,
+}
+
+impl ThistlethwaiteSolution {
+    /// Returns the operations of all phases.
+    pub fn operations(&self) -> Vec<Operation> {
+        self.phases
+            .iter()
+            .flat_map(|phase| phase.operations.clone())
+            .collect()
+    }
+}
+
+impl Display for ThistlethwaiteSolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for phase in &self.phases {
+            let operations = phase
+                .operations
+                .iter()
+                .map(|op| op.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}: {}", phase.subgroup, operations)?;
+        }
+        Ok(())
+    }
+}
+
+/// Solves the cube with Thistlethwaite's algorithm, reaching the subgroups
+/// G1 ⊃ G2 ⊃ G3 ⊃ {e} in turn with the shortest operations in each phase.
+///
+/// The distance tables of the phases are generated on first use.
+pub fn solve_thistlethwaite(cube: &Cube) -> Result<ThistlethwaiteSolution> {
+    solve_thistlethwaite_with_stats(cube).map(|(solution, _)| solution)
+}
+
+/// Solves the cube as [`solve_thistlethwaite`] and returns the statistics
+/// of the searches of all phases as well.
+pub fn solve_thistlethwaite_with_stats(
+    cube: &Cube,
+) -> Result<(ThistlethwaiteSolution, SearchStats)> {
+    let expanded = Cell::new(0);
+    let mut rotation = cube.rotation.clone();
+    let mut phases = vec![];
+
+    for phase in [&*PHASE1, &*PHASE2, &*PHASE3, &*PHASE4] {
+        if phase.table.distance(&rotation).is_none() {
+            return Err(anyhow!("the cube cannot reach {}", phase.subgroup));
+        }
+
+        let root = SearchNode::root(PhaseState {
+            sequence: MoveAutomaton::START,
+            rotation: rotation.clone(),
+            phase,
+            expanded: &expanded,
+        });
+        let node = search::iterative_deepening_dfs(root, phase.max_depth)
+            .ok_or_else(|| anyhow!("no operations found to reach {}", phase.subgroup))?;
+
+        rotation = node.state().rotation.clone();
+        phases.push(PhaseSolution {
+            subgroup: phase.subgroup,
+            operations: node.path(),
+        });
+    }
+
+    Ok((
+        ThistlethwaiteSolution { phases },
+        SearchStats {
+            nodes: expanded.get(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sizes() {
+        assert_eq!(PHASE1.table.size(), 2048);
+        assert_eq!(PHASE2.table.size(), 1082565);
+        assert_eq!(PHASE3.table.size(), 29400);
+        // 6912 edge permutations in 232 classes up to the 48 symmetries,
+        // times 96 corner permutations
+        assert_eq!(PHASE4.table.size(), 22272);
+    }
+
+    #[test]
+    fn test_solve_thistlethwaite() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        let scramble = [R, U3, F, L2, D, B3, R2, U, F3, L, D2, B];
+        for op in &scramble {
+            cube.apply_operation(op);
+        }
+
+        let solution = solve_thistlethwaite(&cube)?;
+        for op in solution.operations() {
+            cube.apply_operation(&op);
+        }
+
+        assert_eq!(solution.phases.len(), 4);
+        assert_eq!(cube, Cube::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_thistlethwaite_phases() -> anyhow::Result<()> {
+        let mut cube = Cube::new();
+        for op in [R2, U, F2] {
+            cube.apply_operation(&op);
+        }
+
+        let (solution, stats) = solve_thistlethwaite_with_stats(&cube)?;
+
+        assert!(stats.nodes > 0);
+        assert!(solution.phases[0].operations.is_empty());
+        assert!(solution.phases[1].operations.is_empty());
+        assert_eq!(solution.phases[2].operations, vec![F2, U]);
+        assert!(solution.phases[3].operations.len() <= 2);
+
+        Ok(())
+    }
+}